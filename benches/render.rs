@@ -0,0 +1,121 @@
+//! Rendering benchmarks on a large keymap, run on the host with:
+//! `cargo bench --target "$(rustc -vV | sed -n 's/host: //p')"`
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use zellij_tile::prelude::actions::Action;
+use zellij_tile::prelude::*;
+
+use oneliner::{Config, KeybindIndex, RenderCache, StatusLine};
+
+static MEASUREMENT_TIME: Duration = Duration::from_secs(2);
+// Number of extra keybinds per mode, on top of the usual ones
+static EXTRA_KEYBINDS: usize = 2000;
+
+fn large_keymap() -> ModeInfo {
+    let modes = [
+        InputMode::Normal,
+        InputMode::Locked,
+        InputMode::Pane,
+        InputMode::Tab,
+        InputMode::Resize,
+        InputMode::Move,
+        InputMode::Scroll,
+        InputMode::Search,
+        InputMode::Session,
+    ];
+
+    let keybinds = modes
+        .iter()
+        .map(|mode| {
+            let mut keybinds = vec![
+                (Key::Ctrl('g'), vec![Action::SwitchToMode(InputMode::Locked)]),
+                (Key::Ctrl('p'), vec![Action::SwitchToMode(InputMode::Pane)]),
+                (Key::Ctrl('t'), vec![Action::SwitchToMode(InputMode::Tab)]),
+                (Key::Ctrl('n'), vec![Action::SwitchToMode(InputMode::Resize)]),
+                (Key::Ctrl('h'), vec![Action::SwitchToMode(InputMode::Move)]),
+                (Key::Ctrl('s'), vec![Action::SwitchToMode(InputMode::Scroll)]),
+                (Key::Ctrl('o'), vec![Action::SwitchToMode(InputMode::Session)]),
+                (Key::Ctrl('q'), vec![Action::Quit]),
+                (Key::Char('\n'), vec![Action::SwitchToMode(InputMode::Normal)]),
+                (Key::Alt(CharOrArrow::Char('n')), vec![Action::NewPane(None, None)]),
+                (Key::Alt(CharOrArrow::Char('h')), vec![Action::MoveFocusOrTab(Direction::Left)]),
+                (Key::Alt(CharOrArrow::Char('j')), vec![Action::MoveFocus(Direction::Down)]),
+                (Key::Alt(CharOrArrow::Char('k')), vec![Action::MoveFocus(Direction::Up)]),
+                (Key::Alt(CharOrArrow::Char('l')), vec![Action::MoveFocusOrTab(Direction::Right)]),
+            ];
+            // Plenty of unrelated keybinds, as with heavily customised configurations
+            keybinds.extend((0..EXTRA_KEYBINDS).map(|i| {
+                let key = match i % 3 {
+                    0 => Key::F((i % 256) as u8),
+                    1 => Key::Ctrl(char::from_u32(0x4e00 + i as u32).unwrap_or('x')),
+                    _ => Key::Alt(CharOrArrow::Char(char::from_u32(0x4e00 + i as u32).unwrap_or('x'))),
+                };
+                (key, vec![Action::GoToTab(i as u32), Action::SwitchToMode(InputMode::Normal)])
+            }));
+
+            (*mode, keybinds)
+        })
+        .collect();
+
+    ModeInfo {
+        mode: InputMode::Normal,
+        keybinds,
+        ..ModeInfo::default()
+    }
+}
+
+/// Runs `f` repeatedly for `MEASUREMENT_TIME`, and reports the average time per iteration
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) -> Duration {
+    let (mut iterations, start) = (0u32, Instant::now());
+    while start.elapsed() < MEASUREMENT_TIME {
+        black_box(f());
+        iterations += 1;
+    }
+
+    let per_iteration = start.elapsed() / iterations;
+    println!("{name:<40} {:>12.2?}/iter ({iterations} iterations)", per_iteration);
+    per_iteration
+}
+
+fn main() {
+    let config = Config::default();
+    let tabs = vec![TabInfo::default()];
+    let width = 200;
+
+    for mode in [InputMode::Normal, InputMode::Pane, InputMode::Resize] {
+        let mode_info = ModeInfo {
+            mode,
+            ..large_keymap()
+        };
+        println!("{mode:?} mode, {} keybinds per mode", mode_info.get_mode_keybinds().len());
+
+        // Indexed once per `ModeUpdate` by the plugin
+        bench("index keybinds", || KeybindIndex::new(&mode_info));
+        let keybind_index = KeybindIndex::new(&mode_info);
+
+        let render = || StatusLine::builder(&mode_info).keybind_index(&keybind_index).config(&config).width(width).build();
+
+        let uncached = bench("render without cache", || render().to_string());
+
+        let mut cache = RenderCache::default();
+        let cached = bench("render with cache", || cache.get_or_render(&mode_info, &tabs, width, 0, render).len());
+
+        // Appending to the whole line so far, as the status line used to
+        let status = render();
+        let concatenated = bench("serialise by concatenation", || {
+            status
+                .spans()
+                .iter()
+                .fold(String::new(), |line, span| format!("{}{}", line, span.style.paint(span.text.clone())))
+        });
+        let buffered = bench("serialise into a single buffer", || status.to_ansi());
+
+        println!(
+            "cache: {:.0}x faster, single buffer: {:.1}x faster\n",
+            uncached.as_secs_f64() / cached.as_secs_f64(),
+            concatenated.as_secs_f64() / buffered.as_secs_f64(),
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Renders the bar on the host, for iterating on themes and configurations without restarting Zellij.
+//!
+//! Keybinds and themes are read from a Zellij configuration, and plugin options from a file of `key "value"` lines,
+//! as found in the plugin's block of a layout.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
+
+use zellij_tile::prelude::*;
+use zellij_utils::input::config::Config as ZellijConfig;
+use zellij_utils::shared::default_palette;
+
+use oneliner::{Config, StatusLine};
+
+static DEFAULT_WIDTHS: [usize; 3] = [80, 120, 160];
+static MODES: [InputMode; 14] = [
+    InputMode::Normal,
+    InputMode::Locked,
+    InputMode::Pane,
+    InputMode::Tab,
+    InputMode::Resize,
+    InputMode::Move,
+    InputMode::Scroll,
+    InputMode::EnterSearch,
+    InputMode::Search,
+    InputMode::RenameTab,
+    InputMode::RenamePane,
+    InputMode::Session,
+    InputMode::Tmux,
+    InputMode::Prompt,
+];
+static USAGE: &str = "Usage: oneliner-preview [OPTIONS]
+
+Options:
+    --config <PATH>          Zellij configuration to read keybinds and themes from [default: Zellij's defaults]
+    --theme <NAME>           Theme of the Zellij configuration to render with [default: the configuration's theme]
+    --plugin-config <PATH>   File of `key \"value\"` lines, as in the plugin's block of a layout
+    --option <KEY=VALUE>     Plugin option, overriding the plugin configuration file
+    --width <WIDTHS>         Comma-separated widths to render at [default: 80,120,160]
+    --mode <MODES>           Comma-separated modes to render [default: all]
+    --simplified-ui          Render without arrow fonts
+    --format <FORMAT>        One of `ansi`, `plain` or `json` [default: ansi]
+    --help                   Print this message";
+
+#[derive(Clone, Copy)]
+enum Format {
+    Ansi,
+    Plain,
+    Json,
+}
+
+struct Options {
+    config: Option<PathBuf>,
+    theme: Option<String>,
+    plugin_config: Option<PathBuf>,
+    options: BTreeMap<String, String>,
+    widths: Vec<usize>,
+    modes: Vec<InputMode>,
+    simplified_ui: bool,
+    format: Format,
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("oneliner-preview: {message}\n\n{USAGE}");
+    process::exit(2);
+}
+
+fn parse_list<T: FromStr>(value: &str, name: &str) -> Vec<T> {
+    value
+        .split(',')
+        .map(|item| item.trim().parse().unwrap_or_else(|_| fail(&format!("invalid {name} `{item}`"))))
+        .collect()
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Options {
+        let mut options = Options {
+            config: None,
+            theme: None,
+            plugin_config: None,
+            options: BTreeMap::new(),
+            widths: DEFAULT_WIDTHS.to_vec(),
+            modes: MODES.to_vec(),
+            simplified_ui: false,
+            format: Format::Ansi,
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().unwrap_or_else(|| fail(&format!("missing value for `{arg}`")));
+
+            match &arg[..] {
+                "--config" => options.config = Some(PathBuf::from(value())),
+                "--theme" => options.theme = Some(value()),
+                "--plugin-config" => options.plugin_config = Some(PathBuf::from(value())),
+                "--option" => {
+                    let option = value();
+                    let Some((key, value)) = option.split_once('=') else { fail(&format!("invalid option `{option}`")) };
+                    options.options.insert(key.trim().to_string(), value.trim().to_string());
+                }
+                "--width" => options.widths = parse_list(&value(), "width"),
+                "--mode" => options.modes = parse_list(&value(), "mode"),
+                "--simplified-ui" => options.simplified_ui = true,
+                "--format" => {
+                    options.format = match &value()[..] {
+                        "ansi" => Format::Ansi,
+                        "plain" => Format::Plain,
+                        "json" => Format::Json,
+                        format => fail(&format!("unknown format `{format}`")),
+                    }
+                }
+                "--help" | "-h" => {
+                    println!("{USAGE}");
+                    process::exit(0);
+                }
+                _ => fail(&format!("unknown argument `{arg}`")),
+            }
+        }
+
+        options
+    }
+
+    /// Plugin options from the configuration file, overridden by those given on the command line
+    fn plugin_configuration(&self) -> BTreeMap<String, String> {
+        let mut configuration = BTreeMap::new();
+
+        if let Some(path) = &self.plugin_config {
+            let contents = fs::read_to_string(path)
+                .unwrap_or_else(|err| fail(&format!("cannot read `{}`: {err}", path.display())));
+
+            for line in contents.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with("//")) {
+                let Some((key, value)) = line.split_once(char::is_whitespace) else { continue };
+                configuration.insert(key.to_string(), value.trim().trim_end_matches(';').trim_matches('"').to_string());
+            }
+        }
+        configuration.extend(self.options.clone());
+
+        configuration
+    }
+
+    fn zellij_config(&self) -> ZellijConfig {
+        let config = match &self.config {
+            Some(path) => ZellijConfig::from_path(path, Some(ZellijConfig::default())),
+            None => ZellijConfig::from_default_assets(),
+        };
+
+        config.unwrap_or_else(|err| {
+            eprintln!("oneliner-preview: cannot load Zellij configuration: {err}");
+            process::exit(1);
+        })
+    }
+}
+
+fn main() {
+    let options = Options::parse(std::env::args().skip(1));
+    let zellij_config = options.zellij_config();
+    let config = Config::from_configuration(&options.plugin_configuration());
+
+    // The theme given on the command line, otherwise the one set in the configuration, as Zellij would pick it
+    let colors = match options.theme.as_ref().or(zellij_config.options.theme.as_ref()) {
+        Some(name) => match zellij_config.themes.get_theme(name) {
+            Some(theme) => theme.palette,
+            None => fail(&format!("unknown theme `{name}`")),
+        },
+        None => default_palette(),
+    };
+
+    let mut mode_info = ModeInfo {
+        keybinds: zellij_config.keybinds.to_keybinds_vec(),
+        style: Style {
+            colors,
+            ..Style::default()
+        },
+        capabilities: PluginCapabilities {
+            arrow_fonts: options.simplified_ui,
+        },
+        ..ModeInfo::default()
+    };
+
+    for mode in &options.modes {
+        mode_info.mode = *mode;
+
+        for width in &options.widths {
+            let status = StatusLine::builder(&mode_info).config(&config).width(*width).build();
+
+            match options.format {
+                Format::Ansi => println!("{mode:?} @ {width}\n{status}\u{1b}[0m"),
+                Format::Plain => println!("{mode:?} @ {width}\n{}", status.to_plain()),
+                Format::Json => println!("{{\"mode\":\"{mode:?}\",\"width\":{width},\"spans\":{}}}", status.to_json()),
+            }
+        }
+    }
+}
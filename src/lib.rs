@@ -0,0 +1,23 @@
+//! Rendering core of the oneliner status bar, reusable from other Zellij plugins.
+//!
+//! ```ignore
+//! let status = StatusLine::builder(&mode_info).config(&config).width(cols).build();
+//! print!("{status}");
+//! ```
+
+mod modules;
+mod plugin;
+
+pub use modules::colored_elements::SegmentLevel;
+pub use modules::config::Config;
+pub use modules::keybind_index::KeybindIndex;
+pub use modules::notification::Notifications;
+pub use modules::segments::Segments;
+pub use modules::span::{Role, Span};
+pub use modules::status_line::{StatusLine, StatusLineBuilder};
+pub use modules::tips::Tip;
+pub use plugin::State;
+
+// Not part of the stable API, only exposed for the benchmarks
+#[doc(hidden)]
+pub use modules::render_cache::RenderCache;
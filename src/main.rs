@@ -1,67 +1,12 @@
-mod modules;
-
+#[cfg(target_family = "wasm")]
 use zellij_tile::prelude::*;
 
-use modules::colored_elements::ColoredElements;
-use modules::status_line::StatusLine;
-
-static ARROW_SEPARATOR: &str = "";
-
-#[derive(Default)]
-struct State {
-    tabs: Vec<TabInfo>,
-    mode_info: ModeInfo,
-}
-
-impl ZellijPlugin for State {
-    fn load(&mut self, _: std::collections::BTreeMap<String, String>) {
-        set_selectable(true);
-        request_permission(&[PermissionType::ReadApplicationState]);
-        subscribe(&[
-            EventType::ModeUpdate,
-            EventType::TabUpdate,
-            EventType::PermissionRequestResult,
-        ]);
-    }
+#[cfg(target_family = "wasm")]
+register_plugin!(oneliner::State);
 
-    fn update(&mut self, event: Event) -> bool {
-        let mut should_render = false;
-
-        match event {
-            Event::ModeUpdate(mode_info) => {
-                should_render = self.mode_info != mode_info;
-                self.mode_info = mode_info;
-            }
-            Event::TabUpdate(tabs) => {
-                should_render = self.tabs != tabs;
-                self.tabs = tabs;
-            }
-            Event::PermissionRequestResult(_) => {
-                should_render = true;
-                set_selectable(false);
-                unsubscribe(&[EventType::PermissionRequestResult]);
-            }
-            _ => {}
-        }
-
-        should_render
-    }
-
-    fn render(&mut self, _rows: usize, cols: usize) {
-        let mode_info = &(self.mode_info);
-        let simplified_ui = mode_info.capabilities.arrow_fonts;
-        let colored_elements = ColoredElements::color_elements(&(mode_info.style.colors), simplified_ui);
-
-        let status = StatusLine::build(
-            mode_info,
-            &(mode_info.get_mode_keybinds()),
-            &colored_elements,
-            simplified_ui,
-            if simplified_ui { "" } else { ARROW_SEPARATOR },
-            cols,
-        );
-        print!("{status}");
-    }
+// The plugin only runs within Zellij, it is only built on the host along with the benchmarks
+#[cfg(not(target_family = "wasm"))]
+fn main() {
+    eprintln!("oneliner is a Zellij plugin: build it with `--target wasm32-wasi`, and load it from Zellij");
+    std::process::exit(1);
 }
-
-register_plugin!(State);
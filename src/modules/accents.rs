@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use ansi_term::Colour;
+use strum::EnumString;
+use zellij_tile::prelude::*;
+
+use super::theme::Theme;
+
+static ACCENT_PREFIX: &str = "accent_";
+
+/// Colors of the theme accents can be named after
+#[derive(Clone, Copy, EnumString, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum NamedColor {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Orange,
+}
+
+/// Accent color, either named after a color of the theme, or given as `#rrggbb`
+#[derive(Clone, Copy, PartialEq)]
+pub enum Accent {
+    Named(NamedColor),
+    Rgb(u8, u8, u8),
+}
+
+impl FromStr for Accent {
+    type Err = strum::ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let Some(hex) = value.strip_prefix('#') else { return value.parse().map(Accent::Named) };
+
+        match u32::from_str_radix(hex, 16) {
+            Ok(rgb) if hex.len() == 6 => Ok(Accent::Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)),
+            _ => Err(strum::ParseError::VariantNotFound),
+        }
+    }
+}
+
+impl Accent {
+    pub fn colour(&self, theme: Theme, palette: &Palette) -> Colour {
+        match self {
+            Accent::Named(name) => theme.named_colour(palette, *name),
+            Accent::Rgb(r, g, b) => Colour::RGB(*r, *g, *b),
+        }
+    }
+}
+
+/// Policy: accent of each mode unless configured, modes sharing tiles share their accent
+fn default_accent(mode: InputMode) -> NamedColor {
+    match mode {
+        InputMode::Normal | InputMode::Pane | InputMode::RenamePane | InputMode::Prompt => NamedColor::Green,
+        InputMode::Locked => NamedColor::Red,
+        InputMode::Tab | InputMode::RenameTab => NamedColor::Blue,
+        InputMode::Resize => NamedColor::Orange,
+        InputMode::Move => NamedColor::Magenta,
+        InputMode::Scroll | InputMode::EnterSearch | InputMode::Search => NamedColor::Cyan,
+        InputMode::Session | InputMode::Tmux => NamedColor::Yellow,
+    }
+}
+
+/// Accent color of each mode, shown on the selected tile and the superkey
+#[derive(Clone, Default)]
+pub struct ModeAccents {
+    // Configured accents, overriding the defaults
+    accents: Vec<(InputMode, Accent)>,
+}
+
+impl ModeAccents {
+    /// Collects every `accent_<mode>` entry, e.g. `accent_locked "#ff0000"`
+    pub fn from_configuration(configuration: &BTreeMap<String, String>) -> ModeAccents {
+        let accents = configuration
+            .iter()
+            .filter_map(|(key, value)| {
+                let mode = InputMode::from_str(key.strip_prefix(ACCENT_PREFIX)?).ok()?;
+                Some((mode, value.trim().parse().ok()?))
+            })
+            .collect();
+
+        ModeAccents { accents }
+    }
+
+    pub fn accent(&self, mode: InputMode) -> Accent {
+        match self.accents.iter().find(|(accent_mode, _)| *accent_mode == mode) {
+            Some((_, accent)) => *accent,
+            None => Accent::Named(default_accent(mode)),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use ansi_term::{Colour, Style};
+use strum::EnumString;
+
+/// Colors the terminal can show. Plugins don't see the terminal's environment, so it can't be detected.
+#[derive(Clone, Copy, Default, EnumString, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum ColorDepth {
+    #[default]
+    Truecolor,
+    #[strum(serialize = "256")]
+    Ansi256,
+    /// The 8 basic colors and their bright variants, the latter written as the first 16 of the 256 colors
+    #[strum(serialize = "16")]
+    Ansi16,
+}
+
+// xterm's defaults for the 16 system colors, which terminals are free to change
+static SYSTEM_COLOURS: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+static CUBE_LEVELS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+/// Approximate RGB value of `colour`, assuming xterm's 256 colors
+pub fn rgb(colour: Colour) -> (u8, u8, u8) {
+    match colour {
+        Colour::Black => SYSTEM_COLOURS[0],
+        Colour::Red => SYSTEM_COLOURS[1],
+        Colour::Green => SYSTEM_COLOURS[2],
+        Colour::Yellow => SYSTEM_COLOURS[3],
+        Colour::Blue => SYSTEM_COLOURS[4],
+        Colour::Purple => SYSTEM_COLOURS[5],
+        Colour::Cyan => SYSTEM_COLOURS[6],
+        Colour::White => SYSTEM_COLOURS[7],
+        Colour::Fixed(idx @ 0..=15) => SYSTEM_COLOURS[idx as usize],
+        Colour::Fixed(idx @ 16..=231) => {
+            let idx = (idx - 16) as usize;
+            (CUBE_LEVELS[idx / 36], CUBE_LEVELS[(idx / 6) % 6], CUBE_LEVELS[idx % 6])
+        }
+        Colour::Fixed(idx) => {
+            let level = 8 + (idx - 232) * 10;
+            (level, level, level)
+        }
+        Colour::RGB(r, g, b) => (r, g, b),
+    }
+}
+
+static ANSI_COLOURS: [Colour; 16] = [
+    Colour::Black,
+    Colour::Red,
+    Colour::Green,
+    Colour::Yellow,
+    Colour::Blue,
+    Colour::Purple,
+    Colour::Cyan,
+    Colour::White,
+    Colour::Fixed(8),
+    Colour::Fixed(9),
+    Colour::Fixed(10),
+    Colour::Fixed(11),
+    Colour::Fixed(12),
+    Colour::Fixed(13),
+    Colour::Fixed(14),
+    Colour::Fixed(15),
+];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel = |a: u8, b: u8| u32::from(a.abs_diff(b)).pow(2);
+    channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
+
+/// Candidate closest to `colour`, the first one on ties
+fn nearest(colour: Colour, candidates: impl Iterator<Item = Colour>) -> Colour {
+    let target = rgb(colour);
+    candidates.min_by_key(|candidate| distance(rgb(*candidate), target)).unwrap_or(colour)
+}
+
+impl ColorDepth {
+    /// Nearest color to `colour` that can be shown with this depth
+    pub fn downsample(self, colour: Colour) -> Colour {
+        match (self, colour) {
+            (ColorDepth::Truecolor, _) => colour,
+            (ColorDepth::Ansi256, Colour::RGB(..)) => nearest(colour, (16..=255).map(Colour::Fixed)),
+            (ColorDepth::Ansi256, _) => colour,
+            (ColorDepth::Ansi16, Colour::Fixed(0..=15)) => colour,
+            (ColorDepth::Ansi16, Colour::RGB(..) | Colour::Fixed(_)) => nearest(colour, ANSI_COLOURS.into_iter()),
+            (ColorDepth::Ansi16, _) => colour,
+        }
+    }
+
+    pub fn downsample_style(self, style: Style) -> Style {
+        Style {
+            foreground: style.foreground.map(|colour| self.downsample(colour)),
+            background: style.background.map(|colour| self.downsample(colour)),
+            ..style
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approximates_256_colors() {
+        assert_eq!(rgb(Colour::Red), (0xcd, 0x00, 0x00));
+        assert_eq!(rgb(Colour::Fixed(9)), (0xff, 0x00, 0x00));
+        assert_eq!(rgb(Colour::Fixed(16)), (0x00, 0x00, 0x00));
+        assert_eq!(rgb(Colour::Fixed(110)), (0x87, 0xaf, 0xd7));
+        assert_eq!(rgb(Colour::Fixed(231)), (0xff, 0xff, 0xff));
+        assert_eq!(rgb(Colour::Fixed(232)), (0x08, 0x08, 0x08));
+        assert_eq!(rgb(Colour::Fixed(255)), (0xee, 0xee, 0xee));
+    }
+
+    #[test]
+    fn keeps_truecolor() {
+        let colour = Colour::RGB(0x12, 0x34, 0x56);
+        assert_eq!(ColorDepth::Truecolor.downsample(colour), colour);
+        assert_eq!(ColorDepth::Truecolor.downsample(Colour::Fixed(42)), Colour::Fixed(42));
+    }
+
+    #[test]
+    fn downsamples_to_256_colors() {
+        let depth = ColorDepth::Ansi256;
+        assert_eq!(depth.downsample(Colour::RGB(0x87, 0xaf, 0xd7)), Colour::Fixed(110));
+        assert_eq!(depth.downsample(Colour::RGB(0x88, 0xb0, 0xd5)), Colour::Fixed(110));
+        assert_eq!(depth.downsample(Colour::RGB(0x30, 0x30, 0x30)), Colour::Fixed(236));
+        // Pure black and white are in the cube, which comes first
+        assert_eq!(depth.downsample(Colour::RGB(0x00, 0x00, 0x00)), Colour::Fixed(16));
+        assert_eq!(depth.downsample(Colour::RGB(0xff, 0xff, 0xff)), Colour::Fixed(231));
+        assert_eq!(depth.downsample(Colour::Blue), Colour::Blue);
+        assert_eq!(depth.downsample(Colour::Fixed(9)), Colour::Fixed(9));
+    }
+
+    #[test]
+    fn downsamples_to_16_colors() {
+        let depth = ColorDepth::Ansi16;
+        assert_eq!(depth.downsample(Colour::RGB(0xc0, 0x10, 0x10)), Colour::Red);
+        assert_eq!(depth.downsample(Colour::RGB(0xf0, 0x20, 0x20)), Colour::Fixed(9));
+        assert_eq!(depth.downsample(Colour::RGB(0x00, 0x00, 0x00)), Colour::Black);
+        assert_eq!(depth.downsample(Colour::RGB(0x80, 0x80, 0x80)), Colour::Fixed(8));
+        assert_eq!(depth.downsample(Colour::RGB(0xe0, 0xe0, 0xe0)), Colour::White);
+        assert_eq!(depth.downsample(Colour::RGB(0xff, 0xff, 0xff)), Colour::Fixed(15));
+        assert_eq!(depth.downsample(Colour::Fixed(196)), Colour::Fixed(9));
+        assert_eq!(depth.downsample(Colour::Fixed(12)), Colour::Fixed(12));
+        assert_eq!(depth.downsample(Colour::Cyan), Colour::Cyan);
+    }
+
+    #[test]
+    fn downsamples_both_colors_of_a_style() {
+        let style = Style::new().fg(Colour::RGB(0xf0, 0x20, 0x20)).on(Colour::RGB(0x00, 0x00, 0x00)).bold();
+        assert_eq!(ColorDepth::Ansi16.downsample_style(style), Style::new().fg(Colour::Fixed(9)).on(Colour::Black).bold());
+    }
+}
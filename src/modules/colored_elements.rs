@@ -1,7 +1,14 @@
-use ansi_term::{ANSIString, Style};
+use ansi_term::{Colour, Style};
+use strum::EnumString;
 use zellij_tile::prelude::*;
 use zellij_tile_utils::style;
 
+use super::color_depth::ColorDepth;
+use super::contrast;
+use super::fonts::FontProfile;
+use super::key_format::{self, KeyNotation};
+use super::span::{Role, Span};
+
 #[derive(Clone, Copy)]
 pub struct ColoredElements {
     // superkey
@@ -17,6 +24,15 @@ pub struct ColoredElements {
     pub key: Style,
     pub text: Style,
     pub filler: Style,
+    // segments
+    pub segment: Style,
+    pub segment_accent: Style,
+    pub segment_warning: Style,
+    pub segment_critical: Style,
+    // key notation
+    pub notation: KeyNotation,
+    // glyphs
+    pub fonts: FontProfile,
 }
 
 #[derive(Clone, Copy)]
@@ -27,10 +43,60 @@ pub struct SegmentStyle {
     pub char_right_separator: Style,
     pub styled_text: Style,
     pub suffix_separator: Style,
+    // Shapes signalling the state of the tile without relying on colors
+    pub marker: &'static str,
+    pub brackets: [&'static str; 2],
+}
+
+#[derive(Clone, Copy, Default, EnumString, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum SegmentLevel {
+    #[default]
+    Normal,
+    Accent,
+    Warning,
+    Critical,
+}
+
+/// How much of the styling reaches the terminal
+#[derive(Clone, Copy, Default, EnumString, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum ColorProfile {
+    #[default]
+    Color,
+    /// Only bold, underline, reverse video and the like
+    Monochrome,
+    /// No styling at all
+    Plain,
+}
+
+impl SegmentStyle {
+    fn map_styles(self, f: impl Fn(Style) -> Style) -> SegmentStyle {
+        SegmentStyle {
+            prefix_separator: f(self.prefix_separator),
+            char_left_separator: f(self.char_left_separator),
+            char_shortcut: f(self.char_shortcut),
+            char_right_separator: f(self.char_right_separator),
+            styled_text: f(self.styled_text),
+            suffix_separator: f(self.suffix_separator),
+            ..self
+        }
+    }
+
+    fn with_minimum_contrast(&mut self, minimum: f64, depth: ColorDepth) {
+        for style in [
+            &mut self.char_left_separator,
+            &mut self.char_shortcut,
+            &mut self.char_right_separator,
+            &mut self.styled_text,
+        ] {
+            *style = contrast::ensure_contrast(*style, minimum, depth);
+        }
+    }
 }
 
 impl ColoredElements {
-    pub fn color_elements(palette: &Palette, different_color_alternates: bool) -> ColoredElements {
+    pub fn color_elements(palette: &Palette, fonts: FontProfile) -> ColoredElements {
         let background = match palette.theme_hue {
             ThemeHue::Dark => palette.black,
             ThemeHue::Light => palette.white,
@@ -39,7 +105,7 @@ impl ColoredElements {
             ThemeHue::Dark => palette.white,
             ThemeHue::Light => palette.black,
         };
-        let alternate_background_color = if different_color_alternates {
+        let alternate_background_color = if fonts.alternate_tile_colors() {
             match palette.theme_hue {
                 ThemeHue::Dark => palette.white,
                 ThemeHue::Light => palette.black,
@@ -59,6 +125,8 @@ impl ColoredElements {
                     char_right_separator: style!(background, palette.green).bold(),
                     styled_text: style!(background, palette.green).bold(),
                     suffix_separator: style!(palette.green, background).bold(),
+                    marker: "",
+                    brackets: ["<", ">"],
                 },
                 unselected: SegmentStyle {
                     prefix_separator: style!(background, palette.fg),
@@ -67,6 +135,8 @@ impl ColoredElements {
                     char_right_separator: style!(background, palette.fg).bold(),
                     styled_text: style!(background, palette.fg).bold(),
                     suffix_separator: style!(palette.fg, background),
+                    marker: "",
+                    brackets: ["<", ">"],
                 },
                 unselected_alternate: SegmentStyle {
                     prefix_separator: style!(background, alternate_background_color),
@@ -75,6 +145,8 @@ impl ColoredElements {
                     char_right_separator: style!(background, alternate_background_color).bold(),
                     styled_text: style!(background, alternate_background_color).bold(),
                     suffix_separator: style!(alternate_background_color, background),
+                    marker: "",
+                    brackets: ["<", ">"],
                 },
                 disabled: SegmentStyle {
                     prefix_separator: style!(background, palette.fg),
@@ -83,11 +155,19 @@ impl ColoredElements {
                     char_right_separator: style!(background, palette.fg).dimmed().italic(),
                     styled_text: style!(background, palette.fg).dimmed().italic(),
                     suffix_separator: style!(palette.fg, background),
+                    marker: "",
+                    brackets: ["<", ">"],
                 },
                 modifier: style!(palette.orange, background).bold(),
                 key: style!(palette.green, background).bold(),
                 text: style!(foreground, background),
                 filler: style!(foreground, background),
+                segment: style!(foreground, background),
+                segment_accent: style!(palette.blue, background).bold(),
+                segment_warning: style!(palette.orange, background).bold(),
+                segment_critical: style!(palette.red, background).bold(),
+                notation: KeyNotation::default(),
+                fonts,
             },
             PaletteSource::Xresources => ColoredElements {
                 superkey_prefix: style!(background, palette.fg).bold(),
@@ -99,6 +179,8 @@ impl ColoredElements {
                     char_right_separator: style!(palette.fg, palette.green).bold(),
                     styled_text: style!(background, palette.green).bold(),
                     suffix_separator: style!(palette.green, background).bold(),
+                    marker: "",
+                    brackets: ["<", ">"],
                 },
                 unselected: SegmentStyle {
                     prefix_separator: style!(background, palette.fg),
@@ -107,6 +189,8 @@ impl ColoredElements {
                     char_right_separator: style!(background, palette.fg).bold(),
                     styled_text: style!(background, palette.fg).bold(),
                     suffix_separator: style!(palette.fg, background),
+                    marker: "",
+                    brackets: ["<", ">"],
                 },
                 unselected_alternate: SegmentStyle {
                     prefix_separator: style!(background, alternate_background_color),
@@ -115,6 +199,8 @@ impl ColoredElements {
                     char_right_separator: style!(background, alternate_background_color).bold(),
                     styled_text: style!(background, alternate_background_color).bold(),
                     suffix_separator: style!(alternate_background_color, background),
+                    marker: "",
+                    brackets: ["<", ">"],
                 },
                 disabled: SegmentStyle {
                     prefix_separator: style!(background, palette.fg),
@@ -123,82 +209,281 @@ impl ColoredElements {
                     char_right_separator: style!(background, palette.fg).dimmed(),
                     styled_text: style!(background, palette.fg).dimmed(),
                     suffix_separator: style!(palette.fg, background),
+                    marker: "",
+                    brackets: ["<", ">"],
                 },
                 modifier: style!(palette.orange, background).bold(),
                 key: style!(palette.green, background).bold(),
                 text: style!(foreground, background),
                 filler: style!(foreground, background),
+                segment: style!(foreground, background),
+                segment_accent: style!(palette.blue, background).bold(),
+                segment_warning: style!(palette.orange, background).bold(),
+                segment_critical: style!(palette.red, background).bold(),
+                notation: KeyNotation::default(),
+                fonts,
             },
         }
     }
 
-    pub fn paint_keys(&self, keys: &[Key]) -> Vec<ANSIString<'static>> {
+    pub fn segment_style(&self, level: SegmentLevel) -> Style {
+        match level {
+            SegmentLevel::Normal => self.segment,
+            SegmentLevel::Accent => self.segment_accent,
+            SegmentLevel::Warning => self.segment_warning,
+            SegmentLevel::Critical => self.segment_critical,
+        }
+    }
+
+    /// Adjusts the foreground of every style printing text to contrast by at least `minimum` with its background, as
+    /// shown at `depth`. Separators are left as is, as they join the backgrounds of their neighbours.
+    pub fn with_minimum_contrast(mut self, minimum: f64, depth: ColorDepth) -> ColoredElements {
+        let fix = |style: &mut Style| *style = contrast::ensure_contrast(*style, minimum, depth);
+
+        fix(&mut self.superkey_prefix);
+        for segment_style in [&mut self.selected, &mut self.unselected, &mut self.unselected_alternate, &mut self.disabled] {
+            segment_style.with_minimum_contrast(minimum, depth);
+        }
+        for style in [
+            &mut self.modifier,
+            &mut self.key,
+            &mut self.text,
+            &mut self.filler,
+            &mut self.segment,
+            &mut self.segment_accent,
+            &mut self.segment_warning,
+            &mut self.segment_critical,
+        ] {
+            fix(style);
+        }
+
+        self
+    }
+
+    /// Paints the selected tile and the superkey with `accent`, and the rest of the line as well with `filler`
+    pub fn with_accent(mut self, accent: Colour, filler: bool) -> ColoredElements {
+        let on_accent = |style: Style| Style {
+            background: Some(accent),
+            ..style
+        };
+        let towards_accent = |style: Style| Style {
+            foreground: Some(accent),
+            ..style
+        };
+
+        self.selected = SegmentStyle {
+            suffix_separator: towards_accent(self.selected.suffix_separator),
+            ..self.selected.map_styles(on_accent)
+        };
+        self.superkey_prefix = Style {
+            foreground: self.selected.styled_text.foreground,
+            ..on_accent(self.superkey_prefix)
+        };
+        self.superkey_suffix_separator = towards_accent(self.superkey_suffix_separator);
+        if filler {
+            self.filler = on_accent(self.filler);
+        }
+
+        self
+    }
+
+    /// Signals the selected and disabled tiles by their shape as well as their colors, for color-blind users
+    pub fn with_shape_cues(mut self) -> ColoredElements {
+        self.selected = SegmentStyle {
+            char_shortcut: self.selected.char_shortcut.reverse(),
+            styled_text: self.selected.styled_text.underline(),
+            marker: self.fonts.glyph("▶", ">"),
+            brackets: ["[", "]"],
+            ..self.selected
+        };
+        self.disabled = SegmentStyle {
+            styled_text: self.disabled.styled_text.strikethrough(),
+            marker: self.fonts.glyph("✕", "x"),
+            ..self.disabled
+        };
+        self
+    }
+
+    /// Restricts the styles to those of `profile`, keeping the layout as is. Without colors, the selected tile is shown
+    /// in reverse video, and segments are told apart by their attributes.
+    pub fn with_color_profile(self, profile: ColorProfile) -> ColoredElements {
+        match profile {
+            ColorProfile::Color => self,
+            ColorProfile::Monochrome => {
+                let mut elements = self.map_styles(|style| Style {
+                    foreground: None,
+                    background: None,
+                    ..style
+                });
+                elements.selected = SegmentStyle {
+                    prefix_separator: elements.selected.prefix_separator,
+                    suffix_separator: elements.selected.suffix_separator,
+                    ..elements.selected.map_styles(|style| style.reverse())
+                };
+                elements.segment_warning = elements.segment_warning.underline();
+                elements.segment_critical = elements.segment_critical.reverse();
+                elements
+            }
+            ColorProfile::Plain => self.map_styles(|_| Style::new()),
+        }
+    }
+
+    /// Replaces every color by the nearest one the terminal can show
+    pub fn with_color_depth(self, depth: ColorDepth) -> ColoredElements {
+        self.map_styles(|style| depth.downsample_style(style))
+    }
+
+    fn map_styles(self, f: impl Fn(Style) -> Style) -> ColoredElements {
+        ColoredElements {
+            superkey_prefix: f(self.superkey_prefix),
+            superkey_suffix_separator: f(self.superkey_suffix_separator),
+            selected: self.selected.map_styles(&f),
+            unselected: self.unselected.map_styles(&f),
+            unselected_alternate: self.unselected_alternate.map_styles(&f),
+            disabled: self.disabled.map_styles(&f),
+            modifier: f(self.modifier),
+            key: f(self.key),
+            text: f(self.text),
+            filler: f(self.filler),
+            segment: f(self.segment),
+            segment_accent: f(self.segment_accent),
+            segment_warning: f(self.segment_warning),
+            segment_critical: f(self.segment_critical),
+            ..self
+        }
+    }
+
+    pub fn with_notation(mut self, notation: KeyNotation) -> ColoredElements {
+        self.notation = notation.resolve(self.fonts);
+        self
+    }
+
+    /// Highlights the hint text with the style of `level`, to draw attention to the bar
+    pub fn escalated(mut self, level: SegmentLevel) -> ColoredElements {
+        self.text = self.segment_style(level);
+        self
+    }
+
+    pub fn paint_keys(&self, keys: &[Key]) -> Vec<Span> {
         if keys.is_empty() { return vec![]; }
 
+        // Group keys by their modifier, in order of first appearance
+        let mut groups: Vec<(Option<&str>, Vec<Key>)> = vec![];
+        for key in keys {
+            let modifier = key_format::modifier(key);
+            match groups.iter_mut().find(|(group_modifier, _)| *group_modifier == modifier) {
+                Some((_, group)) => group.push(*key),
+                None => groups.push((modifier, vec![*key])),
+            }
+        }
+
         let mut ret = vec![];
+        for (idx, (modifier, keys)) in groups.iter().enumerate() {
+            if idx > 0 {
+                ret.push(Span::new(" ", Role::Separator, self.text));
+            }
+            ret.extend(self.paint_key_group(*modifier, keys));
+        }
 
-        let mut modifier_iter = keys
-            .iter()
-            // Filter and retain only modifier keys
-            .filter_map(|key| match key {
-                Key::Ctrl(_) => Some("Ctrl"),
-                Key::Alt(_) => Some("Alt"),
-                _ => None,
-            });
-        let modifier_str = match modifier_iter.next() {
-            // Check if all modifiers are the same, if keys exist
-            Some(modifier) if modifier_iter.all(|str| str == modifier) => modifier.to_string(),
-            _ => String::new(),
-        };
-        let no_modifier = modifier_str.is_empty();
+        ret
+    }
+
+    fn paint_key_group(&self, modifier: Option<&str>, keys: &[Key]) -> Vec<Span> {
+        let mut ret = vec![];
+        let [group_prefix, group_start, group_end] = self.notation.group_delimiters(modifier);
 
         // Prints modifier key
-        let painted_modifier = if modifier_str.is_empty() {
-            Style::new().paint("")
-        } else {
-            self.modifier.paint(modifier_str)
-        };
-        ret.push(painted_modifier);
+        ret.push(Span::new(group_prefix, Role::Separator, self.text));
+        if let Some(modifier) = modifier {
+            ret.push(Span::new(self.notation.modifier_name(modifier), Role::Key, self.modifier));
+        }
 
         // Prints key group start
-        let group_start_str = if no_modifier { "<" } else { " + <" };
-        ret.push(self.text.paint(group_start_str));
+        ret.push(Span::new(group_start, Role::Separator, self.text));
 
         // Prints the keys
-        let key = keys
+        let labels = keys
             .iter()
-            .map(|key| {
-                if no_modifier {
-                    format!("{key}")
-                } else {
-                    match key {
-                        Key::Ctrl(c) => format!("{}", Key::Char(*c)),
-                        Key::Alt(c) => format!("{c}"),
-                        _ => format!("{key}"),
-                    }
-                }
+            .map(|key| match modifier {
+                Some(_) => self.notation.bare_key(key, false),
+                None => self.notation.full_key(key),
             })
             .collect::<Vec<String>>();
 
-        let key_string = key.join("");
-        let key_separator = match &key_string[..] {
-            // Special handling of some pre-defined keygroups
-            "HJKL" | "hjkl" | "←↓↑→" | "←→" | "↓↑" => "",
-            // Default separator
-            _ => "|",
-        };
-
-        for (idx, key) in key.iter().enumerate() {
-            if idx > 0 && !key_separator.is_empty() {
-                ret.push(self.text.paint(key_separator));
+        for (idx, label) in key_format::compress_group(&labels).into_iter().enumerate() {
+            if idx > 0 {
+                ret.push(Span::new("|", Role::Separator, self.text));
             }
-            ret.push(self.key.paint(key.clone()));
+            ret.push(Span::new(self.fonts.key_label(label), Role::Key, self.key));
         }
 
         // Prints key group end
-        let group_end_str = ">";
-        ret.push(self.text.paint(group_end_str));
+        ret.push(Span::new(group_end, Role::Separator, self.text));
 
         ret
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zellij_tile::prelude::*;
+
+    use super::ColoredElements;
+    use crate::modules::fonts::FontProfile;
+    use crate::modules::key_format::KeyNotation;
+    use crate::modules::span;
+
+    fn painted(fonts: FontProfile, notation: KeyNotation, keys: &[Key]) -> String {
+        let colored_elements = ColoredElements::color_elements(&Palette::default(), fonts).with_notation(notation);
+        span::to_plain(&colored_elements.paint_keys(keys))
+    }
+
+    #[test]
+    fn spells_symbols_out_with_ascii_fonts() {
+        let keys = [Key::Ctrl('p'), Key::Alt(CharOrArrow::Char('\n')), Key::Alt(CharOrArrow::Direction(Direction::Left))];
+
+        assert_eq!(painted(FontProfile::Unicode, KeyNotation::Symbolic, &keys), "⌃p ⌥⏎|←");
+        let ascii = painted(FontProfile::Ascii, KeyNotation::Symbolic, &keys);
+        assert_eq!(ascii, "Ctrl+p Alt+ENTER|Left");
+        assert!(ascii.is_ascii());
+    }
+
+    #[test]
+    fn resolves_symbolic_notation_only_with_ascii_fonts() {
+        assert!(KeyNotation::Symbolic.resolve(FontProfile::Ascii) == KeyNotation::Verbose);
+        assert!(KeyNotation::Symbolic.resolve(FontProfile::Unicode) == KeyNotation::Symbolic);
+        assert!(KeyNotation::Vim.resolve(FontProfile::Ascii) == KeyNotation::Vim);
+    }
+
+    #[test]
+    fn groups_keys_by_modifier_in_order_of_appearance() {
+        let keys = [
+            Key::Alt(CharOrArrow::Char('h')),
+            Key::Ctrl('n'),
+            Key::Alt(CharOrArrow::Char('l')),
+            Key::Char('x'),
+            Key::Alt(CharOrArrow::Char('j')),
+            Key::Ctrl('p'),
+            Key::Alt(CharOrArrow::Char('k')),
+        ];
+
+        assert_eq!(painted(FontProfile::Unicode, KeyNotation::Classic, &keys), "Alt + <hjkl> Ctrl + <n|p> <x>");
+    }
+
+    #[test]
+    fn delimits_groups_per_notation() {
+        let keys = [Key::Ctrl('1'), Key::Ctrl('2'), Key::Ctrl('3'), Key::Char('q'), Key::Char('\n')];
+        let painted = |notation| painted(FontProfile::Unicode, notation, &keys);
+
+        assert_eq!(painted(KeyNotation::Classic), "Ctrl + <1-3> <q|ENTER>");
+        assert_eq!(painted(KeyNotation::Verbose), "Ctrl+1-3 q|ENTER");
+        assert_eq!(painted(KeyNotation::Emacs), "C-1-3 q|RET");
+        assert_eq!(painted(KeyNotation::Vim), "<C-1-3> q|<CR>");
+        assert_eq!(painted(KeyNotation::Symbolic), "⌃1-3 q|⏎");
+    }
+
+    #[test]
+    fn paints_no_keys() {
+        assert_eq!(painted(FontProfile::Unicode, KeyNotation::Classic, &[]), "");
+    }
+}
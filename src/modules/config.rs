@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use super::accents::ModeAccents;
+use super::color_depth::ColorDepth;
+use super::colored_elements::{ColorProfile, SegmentLevel};
+use super::contrast::DEFAULT_MINIMUM_CONTRAST;
+use super::fonts::FontProfile;
+use super::key_format::KeyNotation;
+use super::segments::system::Gauge;
+use super::theme::Theme;
+
+static DEFAULT_GIT_REFRESH_INTERVAL: f64 = 10.0;
+static DEFAULT_GIT_TIMEOUT: f64 = 5.0;
+static DEFAULT_COMMAND_REFRESH_INTERVAL: f64 = 10.0;
+static DEFAULT_COMMAND_TIMEOUT: f64 = 5.0;
+// Zellij only maps its working directory into the plugin, under `/host`, so this resolves when it is started from `/`
+static DEFAULT_SYSTEM_PROC_PATH: &str = "/host/proc";
+static DEFAULT_SYSTEM_REFRESH_INTERVAL: f64 = 2.0;
+static DEFAULT_SYSTEM_WARNING_THRESHOLD: f64 = 70.0;
+static DEFAULT_SYSTEM_CRITICAL_THRESHOLD: f64 = 90.0;
+// Like `DEFAULT_SYSTEM_PROC_PATH`, this resolves when Zellij is started from `/`
+static DEFAULT_BATTERY_PATH: &str = "/host/sys/class/power_supply";
+static DEFAULT_BATTERY_REFRESH_INTERVAL: f64 = 30.0;
+static DEFAULT_BATTERY_WARNING_THRESHOLD: f64 = 20.0;
+static DEFAULT_BATTERY_CRITICAL_THRESHOLD: f64 = 10.0;
+static DEFAULT_NOTIFICATION_DURATION: f64 = 5.0;
+static DEFAULT_TIPS_INTERVAL: f64 = 30.0;
+static DEFAULT_TIPS_MAX_VIEWS: usize = 3;
+
+static COMMAND_PREFIX: &str = "command_";
+static COMMAND_OPTIONS: [&str; 5] = ["args", "interval", "timeout", "max_width", "style"];
+
+pub struct Config {
+    // rendering
+    pub key_notation: KeyNotation,
+    pub theme: Theme,
+    pub minimum_contrast: f64,
+    pub color_blind_safe: bool,
+    pub colors: ColorProfile,
+    pub color_depth: ColorDepth,
+    pub fonts: FontProfile,
+    // mode accents
+    pub mode_accents: bool,
+    pub accents: ModeAccents,
+    pub mode_accent_filler: bool,
+    // git segment
+    pub git_segment: bool,
+    pub git_directory: Option<String>,
+    pub git_refresh_interval: f64,
+    pub git_timeout: f64,
+    // command segments
+    pub commands: Vec<CommandConfig>,
+    // system resource segment
+    pub system_segment: bool,
+    pub system_proc_path: String,
+    pub system_gauge: Gauge,
+    pub system_refresh_interval: f64,
+    pub system_warning_threshold: f64,
+    pub system_critical_threshold: f64,
+    // battery segment
+    pub battery_segment: bool,
+    pub battery_path: String,
+    pub battery_refresh_interval: f64,
+    pub battery_warning_threshold: f64,
+    pub battery_critical_threshold: f64,
+    // notifications
+    pub notification_inbox: Option<String>,
+    pub notification_duration: f64,
+    // locked mode
+    pub locked_reminder: Option<f64>,
+    // tips
+    pub tips: bool,
+    pub tips_interval: f64,
+    pub tips_max_views: usize,
+}
+
+#[derive(Clone)]
+pub struct CommandConfig {
+    pub name: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub refresh_interval: f64,
+    pub timeout: f64,
+    pub max_width: Option<usize>,
+    pub style: SegmentLevel,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            key_notation: KeyNotation::default(),
+            theme: Theme::default(),
+            minimum_contrast: DEFAULT_MINIMUM_CONTRAST,
+            color_blind_safe: false,
+            colors: ColorProfile::default(),
+            color_depth: ColorDepth::default(),
+            fonts: FontProfile::default(),
+            mode_accents: false,
+            accents: ModeAccents::default(),
+            mode_accent_filler: false,
+            git_segment: false,
+            git_directory: None,
+            git_refresh_interval: DEFAULT_GIT_REFRESH_INTERVAL,
+            git_timeout: DEFAULT_GIT_TIMEOUT,
+            commands: vec![],
+            system_segment: false,
+            system_proc_path: DEFAULT_SYSTEM_PROC_PATH.to_string(),
+            system_gauge: Gauge::default(),
+            system_refresh_interval: DEFAULT_SYSTEM_REFRESH_INTERVAL,
+            system_warning_threshold: DEFAULT_SYSTEM_WARNING_THRESHOLD,
+            system_critical_threshold: DEFAULT_SYSTEM_CRITICAL_THRESHOLD,
+            battery_segment: false,
+            battery_path: DEFAULT_BATTERY_PATH.to_string(),
+            battery_refresh_interval: DEFAULT_BATTERY_REFRESH_INTERVAL,
+            battery_warning_threshold: DEFAULT_BATTERY_WARNING_THRESHOLD,
+            battery_critical_threshold: DEFAULT_BATTERY_CRITICAL_THRESHOLD,
+            notification_inbox: None,
+            notification_duration: DEFAULT_NOTIFICATION_DURATION,
+            locked_reminder: None,
+            tips: false,
+            tips_interval: DEFAULT_TIPS_INTERVAL,
+            tips_max_views: DEFAULT_TIPS_MAX_VIEWS,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_configuration(configuration: &BTreeMap<String, String>) -> Config {
+        let default = Config::default();
+
+        Config {
+            key_notation: parse(configuration, "key_notation").unwrap_or(default.key_notation),
+            theme: parse(configuration, "theme").unwrap_or(default.theme),
+            minimum_contrast: parse(configuration, "minimum_contrast")
+                .filter(|ratio: &f64| (1.0..=21.0).contains(ratio))
+                .unwrap_or(default.minimum_contrast),
+            color_blind_safe: parse_bool(configuration, "color_blind_safe").unwrap_or(default.color_blind_safe),
+            colors: parse(configuration, "colors").unwrap_or(default.colors),
+            color_depth: parse(configuration, "color_depth").unwrap_or(default.color_depth),
+            fonts: parse(configuration, "fonts").unwrap_or(default.fonts),
+            mode_accents: parse_bool(configuration, "mode_accents").unwrap_or(default.mode_accents),
+            accents: ModeAccents::from_configuration(configuration),
+            mode_accent_filler: parse_bool(configuration, "mode_accent_filler").unwrap_or(default.mode_accent_filler),
+            git_segment: parse_bool(configuration, "git_segment").unwrap_or(default.git_segment),
+            git_directory: configuration.get("git_directory").cloned(),
+            git_refresh_interval: parse_interval(configuration, "git_refresh_interval")
+                .unwrap_or(default.git_refresh_interval),
+            git_timeout: parse_interval(configuration, "git_timeout").unwrap_or(default.git_timeout),
+            commands: CommandConfig::from_configuration(configuration),
+            system_segment: parse_bool(configuration, "system_segment").unwrap_or(default.system_segment),
+            system_proc_path: configuration.get("system_proc_path").cloned().unwrap_or(default.system_proc_path),
+            system_gauge: parse(configuration, "system_gauge").unwrap_or(default.system_gauge),
+            system_refresh_interval: parse_interval(configuration, "system_refresh_interval")
+                .unwrap_or(default.system_refresh_interval),
+            system_warning_threshold: parse(configuration, "system_warning_threshold")
+                .unwrap_or(default.system_warning_threshold),
+            system_critical_threshold: parse(configuration, "system_critical_threshold")
+                .unwrap_or(default.system_critical_threshold),
+            battery_segment: parse_bool(configuration, "battery_segment").unwrap_or(default.battery_segment),
+            battery_path: configuration.get("battery_path").cloned().unwrap_or(default.battery_path),
+            battery_refresh_interval: parse_interval(configuration, "battery_refresh_interval")
+                .unwrap_or(default.battery_refresh_interval),
+            battery_warning_threshold: parse(configuration, "battery_warning_threshold")
+                .unwrap_or(default.battery_warning_threshold),
+            battery_critical_threshold: parse(configuration, "battery_critical_threshold")
+                .unwrap_or(default.battery_critical_threshold),
+            notification_inbox: configuration.get("notification_inbox").cloned(),
+            notification_duration: parse_interval(configuration, "notification_duration")
+                .unwrap_or(default.notification_duration),
+            locked_reminder: parse_interval(configuration, "locked_reminder"),
+            tips: parse_bool(configuration, "tips").unwrap_or(default.tips),
+            tips_interval: parse_interval(configuration, "tips_interval").unwrap_or(default.tips_interval),
+            tips_max_views: parse(configuration, "tips_max_views")
+                .filter(|views: &usize| *views > 0)
+                .unwrap_or(default.tips_max_views),
+        }
+    }
+}
+
+impl CommandConfig {
+    /// Collects every `command_<name>` entry, along with its `command_<name>_<option>` options
+    fn from_configuration(configuration: &BTreeMap<String, String>) -> Vec<CommandConfig> {
+        configuration
+            .iter()
+            .filter_map(|(key, command)| {
+                let name = key.strip_prefix(COMMAND_PREFIX)?;
+
+                // Skip options of other commands
+                let is_option = COMMAND_OPTIONS.iter().any(|option| {
+                    name.strip_suffix(option)
+                        .and_then(|name| name.strip_suffix('_'))
+                        .is_some_and(|name| configuration.contains_key(&format!("{COMMAND_PREFIX}{name}")))
+                });
+                if is_option || command.trim().is_empty() { return None; }
+
+                let option = |option: &str| format!("{key}_{option}");
+                Some(CommandConfig {
+                    name: name.to_string(),
+                    command: command.trim().to_string(),
+                    args: configuration
+                        .get(&option("args"))
+                        .map(|args| split_args(args))
+                        .unwrap_or_default(),
+                    refresh_interval: parse_interval(configuration, &option("interval"))
+                        .unwrap_or(DEFAULT_COMMAND_REFRESH_INTERVAL),
+                    timeout: parse_interval(configuration, &option("timeout")).unwrap_or(DEFAULT_COMMAND_TIMEOUT),
+                    max_width: parse(configuration, &option("max_width")).filter(|width: &usize| *width > 0),
+                    style: parse(configuration, &option("style")).unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Splits `args` on whitespace, except within single or double quotes, e.g. `-c 'echo "a b"'` into `-c` and `echo "a b"`
+fn split_args(args: &str) -> Vec<String> {
+    let mut split = vec![];
+    let mut arg: Option<String> = None;
+    let mut quote = None;
+
+    for c in args.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => arg.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                arg.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => split.extend(arg.take()),
+            (None, c) => arg.get_or_insert_with(String::new).push(c),
+        }
+    }
+    split.extend(arg);
+
+    split
+}
+
+pub fn parse<T: FromStr>(configuration: &BTreeMap<String, String>, key: &str) -> Option<T> {
+    configuration.get(key).and_then(|value| value.trim().parse().ok())
+}
+
+pub fn parse_bool(configuration: &BTreeMap<String, String>, key: &str) -> Option<bool> {
+    match configuration.get(key).map(|value| value.trim().to_lowercase()) {
+        Some(value) if matches!(&value[..], "true" | "yes" | "on" | "1") => Some(true),
+        Some(value) if matches!(&value[..], "false" | "no" | "off" | "0") => Some(false),
+        _ => None,
+    }
+}
+
+/// Parses a positive number of seconds
+pub fn parse_interval(configuration: &BTreeMap<String, String>, key: &str) -> Option<f64> {
+    parse(configuration, key).filter(|interval: &f64| *interval > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_args;
+
+    #[test]
+    fn splits_args_on_whitespace() {
+        assert_eq!(split_args("  +%H:%M   -u "), ["+%H:%M", "-u"]);
+        assert!(split_args("").is_empty());
+    }
+
+    #[test]
+    fn keeps_quoted_args_whole() {
+        assert_eq!(split_args(r#"-c 'echo "a b"' "it's""#), ["-c", r#"echo "a b""#, "it's"]);
+        assert_eq!(split_args(r#"--format="%h %s" ''"#), ["--format=%h %s", ""]);
+    }
+}
@@ -0,0 +1,109 @@
+use ansi_term::{Colour, Style};
+
+use super::color_depth::{rgb, ColorDepth};
+
+/// Contrast ratio for normal text to pass WCAG's AA level
+pub static DEFAULT_MINIMUM_CONTRAST: f64 = 4.5;
+/// Contrast ratio for normal text to pass WCAG's AAA level
+pub static HIGH_CONTRAST: f64 = 7.0;
+
+/// Relative luminance of `colour`, as defined by WCAG
+pub fn luminance(colour: Colour) -> f64 {
+    let channel = |value: u8| {
+        let value = f64::from(value) / 255.0;
+        if value <= 0.03928 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) }
+    };
+
+    let (r, g, b) = rgb(colour);
+    0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+}
+
+/// WCAG contrast ratio between two colors, from 1 for identical colors to 21 for black on white
+pub fn contrast_ratio(a: Colour, b: Colour) -> f64 {
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+fn blend(from: (u8, u8, u8), to: (u8, u8, u8), ratio: f64) -> Colour {
+    let channel = |from: u8, to: u8| (f64::from(from) + (f64::from(to) - f64::from(from)) * ratio).round() as u8;
+    Colour::RGB(channel(from.0, to.0), channel(from.1, to.1), channel(from.2, to.2))
+}
+
+// The 16 colors of the terminal's palette, whose actual values are up to its theme
+fn is_palette_colour(colour: Colour) -> bool {
+    !matches!(colour, Colour::RGB(..) | Colour::Fixed(16..=255))
+}
+
+/// Foreground of `style`, lightened or darkened as little as possible to contrast by at least `minimum` with its
+/// background, once both are shown at `depth`. Styles without both colors, or using one of the terminal's 16 palette
+/// colors, are left as is, as their contrast depends on the terminal, and so are dimmed ones, whose low contrast is
+/// deliberate.
+pub fn ensure_contrast(style: Style, minimum: f64, depth: ColorDepth) -> Style {
+    let (Some(foreground), Some(background)) = (style.foreground, style.background) else { return style };
+    if style.is_dimmed || is_palette_colour(foreground) || is_palette_colour(background) { return style; }
+    let (foreground, background) = (depth.downsample(foreground), depth.downsample(background));
+    if contrast_ratio(foreground, background) >= minimum { return style; }
+
+    let (black, white) = (depth.downsample(Colour::RGB(0, 0, 0)), depth.downsample(Colour::RGB(255, 255, 255)));
+    // Moving away from the background's luminance keeps the hue, when that's not enough the best extreme is used
+    let away = if luminance(foreground) >= luminance(background) { white } else { black };
+    if contrast_ratio(away, background) < minimum {
+        let extreme = if contrast_ratio(black, background) > contrast_ratio(white, background) { black } else { white };
+        return style.fg(extreme);
+    }
+
+    // The contrast only increases along the way, so the smallest sufficient blend is found by bisection
+    let (from, target, mut low, mut high) = (rgb(foreground), rgb(away), 0.0, 1.0);
+    for _ in 0..16 {
+        let middle = (low + high) / 2.0;
+        let candidate = depth.downsample(blend(from, target, middle));
+        if contrast_ratio(candidate, background) >= minimum { high = middle } else { low = middle }
+    }
+
+    // Quantizing may break the ordering the bisection relies on, so the result is checked once more
+    let adjusted = depth.downsample(blend(from, target, high));
+    if contrast_ratio(adjusted, background) >= minimum { style.fg(adjusted) } else { style.fg(away) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contrast_holds_once_downsampled() {
+        let style = Style::new().fg(Colour::RGB(0x80, 0x80, 0x80)).on(Colour::RGB(0x60, 0x60, 0x60));
+
+        for depth in [ColorDepth::Truecolor, ColorDepth::Ansi256] {
+            let adjusted = ensure_contrast(depth.downsample_style(style), DEFAULT_MINIMUM_CONTRAST, depth);
+            let (foreground, background) = (adjusted.foreground.unwrap(), adjusted.background.unwrap());
+            assert_eq!(depth.downsample(foreground), foreground);
+            assert!(contrast_ratio(foreground, background) >= DEFAULT_MINIMUM_CONTRAST);
+        }
+    }
+
+    #[test]
+    fn leaves_palette_colors() {
+        let low_contrast = [
+            Style::new().fg(Colour::Fixed(8)).on(Colour::Black),
+            Style::new().fg(Colour::Fixed(15)).on(Colour::RGB(0xe0, 0xe0, 0xe0)),
+            Style::new().fg(Colour::RGB(0x30, 0x30, 0x30)).on(Colour::Blue),
+        ];
+
+        for style in low_contrast {
+            assert_eq!(ensure_contrast(style, DEFAULT_MINIMUM_CONTRAST, ColorDepth::Truecolor), style);
+        }
+        // At 16 colors, only palette colors are left
+        let style = ColorDepth::Ansi16.downsample_style(Style::new().fg(Colour::RGB(0x80, 0x80, 0x80)).on(Colour::RGB(0x60, 0x60, 0x60)));
+        assert_eq!(ensure_contrast(style, DEFAULT_MINIMUM_CONTRAST, ColorDepth::Ansi16), style);
+        // Colors past the palette are fixed, so they are adjusted
+        let style = Style::new().fg(Colour::Fixed(240)).on(Colour::Fixed(238));
+        assert_ne!(ensure_contrast(style, DEFAULT_MINIMUM_CONTRAST, ColorDepth::Ansi256), style);
+    }
+
+    #[test]
+    fn leaves_dimmed_styles() {
+        let style = Style::new().fg(Colour::RGB(0x80, 0x80, 0x80)).on(Colour::RGB(0x60, 0x60, 0x60)).dimmed();
+
+        assert_eq!(ensure_contrast(style, DEFAULT_MINIMUM_CONTRAST, ColorDepth::Truecolor), style);
+    }
+}
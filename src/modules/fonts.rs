@@ -0,0 +1,86 @@
+use strum::EnumString;
+use zellij_tile::prelude::*;
+
+static ARROW_SEPARATOR: &str = "";
+
+/// Glyphs the terminal's font can show
+#[derive(Clone, Copy, Default, EnumString, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum FontProfile {
+    /// Detected from Zellij's `simplified_ui` option
+    #[default]
+    Auto,
+    /// Powerline arrows between tiles
+    Powerline,
+    /// Powerline arrows, along with Nerd Font icons in the segments
+    Nerd,
+    /// No font-specific glyphs, tiles are told apart by alternating colors
+    Unicode,
+    /// ASCII characters only
+    Ascii,
+}
+
+impl FontProfile {
+    /// Profile from the capabilities Zellij reports, only `Auto` is resolved
+    pub fn resolve(self, capabilities: &PluginCapabilities) -> FontProfile {
+        if self != FontProfile::Auto { return self; }
+
+        // Despite its name, Zellij sets `arrow_fonts` to the value of its `simplified_ui` option
+        if capabilities.arrow_fonts { FontProfile::Unicode } else { FontProfile::Powerline }
+    }
+
+    /// Separator between tiles, if any
+    pub fn separator(self) -> &'static str {
+        match self {
+            FontProfile::Auto | FontProfile::Powerline | FontProfile::Nerd => ARROW_SEPARATOR,
+            FontProfile::Unicode | FontProfile::Ascii => "",
+        }
+    }
+
+    /// Whether neighbouring tiles alternate colors, to tell them apart without separators
+    pub fn alternate_tile_colors(self) -> bool {
+        self.separator().is_empty()
+    }
+
+    pub fn has_icons(self) -> bool {
+        self == FontProfile::Nerd
+    }
+
+    /// `unicode`, unless only ASCII can be shown
+    pub fn glyph(self, unicode: &'static str, ascii: &'static str) -> &'static str {
+        if self == FontProfile::Ascii { ascii } else { unicode }
+    }
+
+    pub fn ellipsis(self) -> &'static str {
+        self.glyph("…", "...")
+    }
+
+    /// `label` with arrows spelt out when only ASCII can be shown, e.g. `←→` as `Left|Right`
+    pub fn key_label(self, label: String) -> String {
+        if self != FontProfile::Ascii { return label; }
+
+        let mut spelt = String::new();
+        let mut previous_was_arrow = false;
+        for c in label.chars() {
+            let name = match c {
+                '←' => Some("Left"),
+                '↓' => Some("Down"),
+                '↑' => Some("Up"),
+                '→' => Some("Right"),
+                _ => None,
+            };
+            match name {
+                Some(name) => {
+                    if previous_was_arrow {
+                        spelt.push('|');
+                    }
+                    spelt.push_str(name);
+                }
+                None => spelt.push(c),
+            }
+            previous_was_arrow = name.is_some();
+        }
+
+        spelt
+    }
+}
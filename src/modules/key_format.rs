@@ -0,0 +1,405 @@
+use strum::EnumString;
+use zellij_tile::prelude::*;
+
+use super::fonts::FontProfile;
+
+#[derive(Clone, Copy, Default, EnumString, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum KeyNotation {
+    /// `Ctrl + <p>`, as in Zellij's status-bar
+    #[default]
+    Classic,
+    /// `Ctrl+p`
+    Verbose,
+    /// `C-p`
+    Emacs,
+    /// `<C-p>`
+    Vim,
+    /// `⌃p`
+    Symbolic,
+}
+
+/// Modifier held down for `key`, if any
+pub fn modifier(key: &Key) -> Option<&'static str> {
+    match key {
+        Key::Ctrl(_) => Some("Ctrl"),
+        Key::Alt(_) => Some("Alt"),
+        _ => None,
+    }
+}
+
+fn direction(direction: &Direction) -> &'static str {
+    match direction {
+        Direction::Left => "←",
+        Direction::Down => "↓",
+        Direction::Up => "↑",
+        Direction::Right => "→",
+    }
+}
+
+impl KeyNotation {
+    /// Notation `fonts` can show, symbols being spelt out as `Verbose` when only ASCII can be shown
+    pub fn resolve(self, fonts: FontProfile) -> KeyNotation {
+        if self == KeyNotation::Symbolic && fonts == FontProfile::Ascii { KeyNotation::Verbose } else { self }
+    }
+
+    pub fn modifier_name(&self, modifier: &str) -> &'static str {
+        match (self, modifier) {
+            (KeyNotation::Classic | KeyNotation::Verbose, "Ctrl") => "Ctrl",
+            (KeyNotation::Classic | KeyNotation::Verbose, _) => "Alt",
+            (KeyNotation::Emacs | KeyNotation::Vim, "Ctrl") => "C",
+            (KeyNotation::Emacs, _) => "M",
+            (KeyNotation::Vim, _) => "A",
+            (KeyNotation::Symbolic, "Ctrl") => "⌃",
+            (KeyNotation::Symbolic, _) => "⌥",
+        }
+    }
+
+    /// Name of keys which aren't a single character, if any
+    fn special_name(&self, key: &Key) -> Option<&'static str> {
+        use KeyNotation::{Classic, Emacs, Symbolic, Verbose, Vim};
+
+        let name = match (key, self) {
+            (Key::Char('\n') | Key::Ctrl('\n') | Key::Alt(CharOrArrow::Char('\n')), notation) => match notation {
+                Classic | Verbose => "ENTER",
+                Emacs => "RET",
+                Vim => "CR",
+                Symbolic => "⏎",
+            },
+            (Key::Char(' ') | Key::Ctrl(' ') | Key::Alt(CharOrArrow::Char(' ')), notation) => match notation {
+                Classic | Verbose => "SPACE",
+                Emacs => "SPC",
+                Vim => "Space",
+                Symbolic => "␣",
+            },
+            // Zellij 0.39 parses both the Tab key and `Tab` in its configuration as `BackTab`
+            (Key::Char('\t') | Key::Ctrl('\t') | Key::Alt(CharOrArrow::Char('\t')) | Key::BackTab, notation) => {
+                match notation {
+                    Classic | Verbose | Emacs => "TAB",
+                    Vim => "Tab",
+                    Symbolic => "⇥",
+                }
+            }
+            (Key::Esc, notation) => match notation {
+                Classic | Verbose | Emacs => "ESC",
+                Vim => "Esc",
+                Symbolic => "⎋",
+            },
+            (Key::Backspace, notation) => match notation {
+                Classic | Verbose => "BACKSPACE",
+                Emacs => "DEL",
+                Vim => "BS",
+                Symbolic => "⌫",
+            },
+            (Key::Delete, notation) => match notation {
+                Classic | Verbose => "DEL",
+                Emacs => "delete",
+                Vim => "Del",
+                Symbolic => "⌦",
+            },
+            (Key::Insert, notation) => match notation {
+                Classic | Verbose => "INS",
+                Emacs => "insert",
+                Vim => "Insert",
+                Symbolic => "⎀",
+            },
+            (Key::Home, notation) => match notation {
+                Classic | Verbose => "HOME",
+                Emacs => "home",
+                Vim => "Home",
+                Symbolic => "⇱",
+            },
+            (Key::End, notation) => match notation {
+                Classic | Verbose => "END",
+                Emacs => "end",
+                Vim => "End",
+                Symbolic => "⇲",
+            },
+            (Key::PageUp, notation) => match notation {
+                Classic | Verbose => "PgUp",
+                Emacs => "prior",
+                Vim => "PageUp",
+                Symbolic => "⇞",
+            },
+            (Key::PageDown, notation) => match notation {
+                Classic | Verbose => "PgDn",
+                Emacs => "next",
+                Vim => "PageDown",
+                Symbolic => "⇟",
+            },
+            (Key::Null, notation) => match notation {
+                Classic | Verbose => "NUL",
+                Emacs => "C-@",
+                Vim => "Nul",
+                Symbolic => "␀",
+            },
+            _ => return None,
+        };
+
+        Some(name)
+    }
+
+    /// Renders `key` without its modifier, e.g. `Key::Ctrl('p')` as `p`.
+    /// `standalone` keys are shown on their own, rather than as part of a group of keys.
+    pub fn bare_key(&self, key: &Key, standalone: bool) -> String {
+        if let Some(name) = self.special_name(key) {
+            // Vim wraps special keys in angle brackets, unless a modifier does so already
+            return if *self == KeyNotation::Vim && standalone { format!("<{name}>") } else { name.to_string() };
+        }
+
+        match key {
+            Key::Left => direction(&Direction::Left).to_string(),
+            Key::Down => direction(&Direction::Down).to_string(),
+            Key::Up => direction(&Direction::Up).to_string(),
+            Key::Right => direction(&Direction::Right).to_string(),
+            Key::Alt(CharOrArrow::Direction(d)) => direction(d).to_string(),
+            Key::F(n) if *self == KeyNotation::Emacs => format!("f{n}"),
+            Key::F(n) => format!("F{n}"),
+            Key::Char(c) | Key::Ctrl(c) | Key::Alt(CharOrArrow::Char(c)) => c.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// Renders `key` along with its modifier, e.g. `Key::Ctrl('p')` as `Ctrl+p`
+    pub fn full_key(&self, key: &Key) -> String {
+        let Some(modifier) = modifier(key) else { return self.bare_key(key, true) };
+
+        let (modifier, key) = (self.modifier_name(modifier), self.bare_key(key, false));
+        match self {
+            KeyNotation::Classic | KeyNotation::Verbose => format!("{modifier}+{key}"),
+            KeyNotation::Emacs => format!("{modifier}-{key}"),
+            KeyNotation::Vim => format!("<{modifier}-{key}>"),
+            KeyNotation::Symbolic => format!("{modifier}{key}"),
+        }
+    }
+
+    /// Text around a group of keys sharing `modifier`: before the modifier, between the modifier and the keys, and
+    /// after the keys
+    pub fn group_delimiters(&self, modifier: Option<&str>) -> [&'static str; 3] {
+        match (self, modifier) {
+            (KeyNotation::Classic, Some(_)) => ["", " + <", ">"],
+            (KeyNotation::Classic, None) => ["", "<", ">"],
+            (KeyNotation::Verbose, Some(_)) => ["", "+", ""],
+            (KeyNotation::Emacs, Some(_)) => ["", "-", ""],
+            (KeyNotation::Vim, Some(_)) => ["<", "-", ">"],
+            _ => ["", "", ""],
+        }
+    }
+
+    /// Renders the superkey prefix shared by the mode tiles, e.g. `Ctrl +`
+    pub fn superkey(&self, modifier: &str) -> String {
+        let modifier = self.modifier_name(modifier);
+        match self {
+            KeyNotation::Classic => format!("{modifier} +"),
+            KeyNotation::Verbose => format!("{modifier}+"),
+            KeyNotation::Emacs | KeyNotation::Vim => format!("{modifier}-"),
+            KeyNotation::Symbolic => modifier.to_string(),
+        }
+    }
+}
+
+// Sets of keys covering all directions, shown without separators, in this order
+static DIRECTION_SETS: [&[&str]; 5] = [
+    &["h", "j", "k", "l"],
+    &["H", "J", "K", "L"],
+    &["←", "↓", "↑", "→"],
+    &["←", "→"],
+    &["↓", "↑"],
+];
+// Minimal number of consecutive characters compressed into a range
+static MINIMAL_RANGE_LENGTH: usize = 3;
+
+fn single_char(label: &str) -> Option<char> {
+    let mut chars = label.chars();
+    chars.next().filter(|_| chars.next().is_none())
+}
+
+// Whether the characters of `set` are part of a range of consecutive characters among `tokens`, e.g. `hjkl` in `a-z`
+fn is_within_range(set: &[&str], tokens: &[Option<String>]) -> bool {
+    let chars = set.iter().filter_map(|label| single_char(label)).filter(char::is_ascii_alphanumeric);
+    let (Some(first), Some(last)) = (chars.clone().min(), chars.max()) else { return false };
+
+    (first..=last).all(|c| tokens.iter().any(|token| token.as_deref().and_then(single_char) == Some(c)))
+}
+
+/// Compresses the labels of a group of keys into as few tokens as possible, to be separated by `|`:
+/// - direction sets in any order, e.g. `l|k|j|h` into `hjkl`, unless they are part of a range
+/// - case pairs, e.g. `h|H` into `hH`
+/// - consecutive characters, e.g. `1|2|3|4` into `1-4`
+pub fn compress_group(labels: &[String]) -> Vec<String> {
+    let mut tokens = labels.iter().cloned().map(Some).collect::<Vec<Option<String>>>();
+
+    for set in DIRECTION_SETS {
+        let positions = set
+            .iter()
+            .map(|direction| tokens.iter().position(|token| token.as_deref() == Some(*direction)))
+            .collect::<Option<Vec<usize>>>();
+
+        if let Some(positions) = positions.filter(|_| !is_within_range(set, &tokens)) {
+            // Replace the whole set with a single token, where the set first appears
+            let first = positions.iter().copied().min().unwrap_or_default();
+            for position in positions {
+                tokens[position] = None;
+            }
+            tokens[first] = Some(set.concat());
+        }
+    }
+
+    for idx in 0..tokens.len() {
+        let Some(c) = tokens[idx].as_deref().and_then(single_char).filter(char::is_ascii_alphabetic) else { continue };
+        let other_case = if c.is_ascii_lowercase() { c.to_ascii_uppercase() } else { c.to_ascii_lowercase() };
+
+        let pair = tokens.iter().position(|token| token.as_deref().and_then(single_char) == Some(other_case));
+        if let Some(pair) = pair {
+            tokens[pair] = None;
+            tokens[idx] = Some(format!("{}{}", c.to_ascii_lowercase(), c.to_ascii_uppercase()));
+        }
+    }
+
+    let mut compressed = Vec::<String>::new();
+    let mut run = Vec::<char>::new();
+    let flush = |run: &mut Vec<char>, compressed: &mut Vec<String>| {
+        match (run.first(), run.last()) {
+            (Some(first), Some(last)) if run.len() >= MINIMAL_RANGE_LENGTH => compressed.push(format!("{first}-{last}")),
+            _ => compressed.extend(run.iter().map(ToString::to_string)),
+        }
+        run.clear();
+    };
+
+    for token in tokens.into_iter().flatten() {
+        match single_char(&token).filter(char::is_ascii_alphanumeric) {
+            // Extend the current run of consecutive characters
+            Some(c) if run.last().is_some_and(|last| *last as u32 + 1 == c as u32) => run.push(c),
+            Some(c) => {
+                flush(&mut run, &mut compressed);
+                run.push(c);
+            }
+            None => {
+                flush(&mut run, &mut compressed);
+                compressed.push(token);
+            }
+        }
+    }
+    flush(&mut run, &mut compressed);
+
+    compressed
+}
+
+#[cfg(test)]
+mod tests {
+    use zellij_tile::prelude::*;
+
+    use super::compress_group;
+    use super::KeyNotation::{self, Classic, Emacs, Symbolic, Verbose, Vim};
+
+    static NOTATIONS: [KeyNotation; 5] = [Classic, Verbose, Emacs, Vim, Symbolic];
+
+    // Renders `key` in every notation, in the order of `NOTATIONS`
+    fn full_keys(key: Key) -> [String; 5] {
+        NOTATIONS.map(|notation| notation.full_key(&key))
+    }
+
+    #[test]
+    fn formats_characters() {
+        assert_eq!(full_keys(Key::Char('p')), ["p", "p", "p", "p", "p"]);
+        assert_eq!(full_keys(Key::Ctrl('p')), ["Ctrl+p", "Ctrl+p", "C-p", "<C-p>", "⌃p"]);
+        assert_eq!(full_keys(Key::Alt(CharOrArrow::Char('n'))), ["Alt+n", "Alt+n", "M-n", "<A-n>", "⌥n"]);
+    }
+
+    #[test]
+    fn formats_directions() {
+        assert_eq!(full_keys(Key::Left), ["←", "←", "←", "←", "←"]);
+        assert_eq!(full_keys(Key::Down), ["↓", "↓", "↓", "↓", "↓"]);
+        assert_eq!(full_keys(Key::Up), ["↑", "↑", "↑", "↑", "↑"]);
+        assert_eq!(full_keys(Key::Right), ["→", "→", "→", "→", "→"]);
+        assert_eq!(full_keys(Key::Alt(CharOrArrow::Direction(Direction::Up))), ["Alt+↑", "Alt+↑", "M-↑", "<A-↑>", "⌥↑"]);
+    }
+
+    #[test]
+    fn formats_function_keys() {
+        assert_eq!(full_keys(Key::F(5)), ["F5", "F5", "f5", "F5", "F5"]);
+    }
+
+    #[test]
+    fn formats_special_keys() {
+        assert_eq!(full_keys(Key::Char('\n')), ["ENTER", "ENTER", "RET", "<CR>", "⏎"]);
+        assert_eq!(full_keys(Key::Char(' ')), ["SPACE", "SPACE", "SPC", "<Space>", "␣"]);
+        assert_eq!(full_keys(Key::Ctrl(' ')), ["Ctrl+SPACE", "Ctrl+SPACE", "C-SPC", "<C-Space>", "⌃␣"]);
+        assert_eq!(full_keys(Key::Esc), ["ESC", "ESC", "ESC", "<Esc>", "⎋"]);
+        assert_eq!(full_keys(Key::Backspace), ["BACKSPACE", "BACKSPACE", "DEL", "<BS>", "⌫"]);
+        assert_eq!(full_keys(Key::Delete), ["DEL", "DEL", "delete", "<Del>", "⌦"]);
+        assert_eq!(full_keys(Key::Insert), ["INS", "INS", "insert", "<Insert>", "⎀"]);
+        assert_eq!(full_keys(Key::Home), ["HOME", "HOME", "home", "<Home>", "⇱"]);
+        assert_eq!(full_keys(Key::End), ["END", "END", "end", "<End>", "⇲"]);
+        assert_eq!(full_keys(Key::PageUp), ["PgUp", "PgUp", "prior", "<PageUp>", "⇞"]);
+        assert_eq!(full_keys(Key::PageDown), ["PgDn", "PgDn", "next", "<PageDown>", "⇟"]);
+    }
+
+    #[test]
+    fn formats_back_tab_as_tab() {
+        // Zellij's default `bind "Tab"` comes as `BackTab`
+        assert_eq!(full_keys(Key::Char('\t')), ["TAB", "TAB", "TAB", "<Tab>", "⇥"]);
+        assert_eq!(full_keys(Key::BackTab), ["TAB", "TAB", "TAB", "<Tab>", "⇥"]);
+    }
+
+    #[test]
+    fn formats_modified_tab_and_enter() {
+        assert_eq!(full_keys(Key::Ctrl('\t')), ["Ctrl+TAB", "Ctrl+TAB", "C-TAB", "<C-Tab>", "⌃⇥"]);
+        assert_eq!(full_keys(Key::Alt(CharOrArrow::Char('\t'))), ["Alt+TAB", "Alt+TAB", "M-TAB", "<A-Tab>", "⌥⇥"]);
+        assert_eq!(full_keys(Key::Ctrl('\n')), ["Ctrl+ENTER", "Ctrl+ENTER", "C-RET", "<C-CR>", "⌃⏎"]);
+        assert_eq!(full_keys(Key::Alt(CharOrArrow::Char('\n'))), ["Alt+ENTER", "Alt+ENTER", "M-RET", "<A-CR>", "⌥⏎"]);
+    }
+
+    #[test]
+    fn formats_null_key() {
+        assert_eq!(full_keys(Key::Null), ["NUL", "NUL", "C-@", "<Nul>", "␀"]);
+    }
+
+    #[test]
+    fn formats_superkey() {
+        let superkeys = NOTATIONS.map(|notation| notation.superkey("Ctrl"));
+        assert_eq!(superkeys, ["Ctrl +", "Ctrl+", "C-", "C-", "⌃"]);
+    }
+
+    fn compressed(labels: &[&str]) -> Vec<String> {
+        compress_group(&labels.iter().map(ToString::to_string).collect::<Vec<String>>())
+    }
+
+    #[test]
+    fn compresses_ranges() {
+        assert_eq!(compressed(&["1", "2", "3", "4", "5", "6", "7", "8", "9"]), ["1-9"]);
+        let letters = ('a'..='z').map(String::from).collect::<Vec<String>>();
+        assert_eq!(compress_group(&letters), ["a-z"]);
+        assert_eq!(compressed(&["1", "2", "3", "x"]), ["1-3", "x"]);
+        // Direction sets within a range are part of the range
+        assert_eq!(compressed(&["h", "i", "j", "k", "l"]), ["h-l"]);
+    }
+
+    #[test]
+    fn keeps_short_runs_apart() {
+        assert_eq!(compressed(&["a", "b"]), ["a", "b"]);
+    }
+
+    #[test]
+    fn keeps_non_contiguous_groups_apart() {
+        assert_eq!(compressed(&["1", "3", "5", "7"]), ["1", "3", "5", "7"]);
+        assert_eq!(compressed(&["a", "b", "d", "e"]), ["a", "b", "d", "e"]);
+    }
+
+    #[test]
+    fn compresses_direction_sets_in_any_order() {
+        assert_eq!(compressed(&["l", "k", "j", "h"]), ["hjkl"]);
+        assert_eq!(compressed(&["H", "J", "K", "L"]), ["HJKL"]);
+        assert_eq!(compressed(&["→", "↑", "↓", "←"]), ["←↓↑→"]);
+        assert_eq!(compressed(&["→", "←"]), ["←→"]);
+        assert_eq!(compressed(&["↑", "↓", "x"]), ["↓↑", "x"]);
+        // Incomplete sets are left as is
+        assert_eq!(compressed(&["h", "j", "k"]), ["h", "j", "k"]);
+    }
+
+    #[test]
+    fn compresses_case_pairs() {
+        assert_eq!(compressed(&["h", "H"]), ["hH"]);
+        assert_eq!(compressed(&["N", "p", "n"]), ["nN", "p"]);
+    }
+}
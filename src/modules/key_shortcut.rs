@@ -1,14 +1,15 @@
 use std::str::FromStr;
 use std::string::ToString;
 
-use ansi_term::ANSIStrings;
 use strum::{Display, EnumIter, EnumProperty, IntoEnumIterator};
 use zellij_tile::prelude::actions::Action;
 use zellij_tile::prelude::*;
 
 use super::colored_elements::ColoredElements;
+use super::key_format::KeyNotation;
+use super::keybind_index::ModeKeybinds;
+use super::span::{Role, Span};
 use super::status_line::StatusLine;
-use super::utils;
 
 #[derive(Clone, Copy, Display, EnumIter, EnumProperty, PartialEq)]
 pub enum KeyAction {
@@ -52,12 +53,12 @@ impl KeyAction {
         }
     }
 
-    fn key_shortcut(&self, keybinds: &[(Key, Vec<Action>)], alternate: bool) -> KeyShortcut {
+    fn key_shortcut(&self, keybinds: &ModeKeybinds, alternate: bool) -> KeyShortcut {
         KeyShortcut::new(
             // Unselect all initially by default
             if alternate { KeyMode::Unselected } else { KeyMode::UnselectedAlternate },
             *self,
-            utils::to_key(keybinds, &[self.action()]),
+            keybinds.key(&[self.action()]),
         )
     }
 }
@@ -80,7 +81,7 @@ impl KeyShortcut {
         KeyShortcut { mode, action, key }
     }
 
-    fn default_shortcuts(keybinds: &[(Key, Vec<Action>)]) -> Vec<Self> {
+    fn default_shortcuts(keybinds: &ModeKeybinds) -> Vec<Self> {
         // Unselect all by default
         KeyAction::iter()
             .enumerate()
@@ -92,91 +93,67 @@ impl KeyShortcut {
         self.action.to_string().to_uppercase()
     }
 
-    fn letter_shortcut_and_count(&self, long: bool, with_prefix: bool) -> (String, usize) {
+    fn letter_shortcut(&self, notation: KeyNotation, marker: &str, long: bool, with_prefix: bool) -> String {
         let key = match self.key {
             Some(k) => k,
             None => Key::Null,
         };
 
-        let key_binding = if with_prefix {
-            format!("{key}")
-        } else {
-            match key {
-                Key::F(c) => format!("{c}"),
-                Key::Ctrl(c) => format!("{c}"),
-                Key::Char(_) => format!("{key}"),
-                Key::Alt(c) => format!("{c}"),
-                _ => String::from("??"),
-            }
-        };
-        let count = key_binding.chars().count();
+        let key_binding = if with_prefix { notation.full_key(&key) } else { notation.bare_key(&key, true) };
 
-        if long {
-            (key_binding, count)
-        } else {
-            (format!(" {key_binding} "), count + 2)
+        if long { key_binding } else { format!("{marker} {key_binding} ") }
+    }
+
+    /// Action carried out by clicking the tile: leaving the selected mode, otherwise entering the tile's mode
+    fn click_target(&self) -> Action {
+        match self.mode {
+            KeyMode::Selected => Action::SwitchToMode(InputMode::Normal),
+            _ => self.action.action(),
         }
     }
 
     pub fn generate_status(
-        &self, colored_elements: &ColoredElements, separator: &str, long: bool, with_prefix: bool, first_tile: bool,
+        &self, colored_elements: &ColoredElements, long: bool, with_prefix: bool, first_tile: bool,
     ) -> StatusLine {
         let key_hint = self.full_text();
-        let (key_binding, count) = match (&self.mode, &self.key) {
-            // Disabled or unreachable mode, don't print
-            (_, None) | (KeyMode::Disabled, _) => return StatusLine::default(),
-            // Reachable mode, print
-            (_, Some(_)) => self.letter_shortcut_and_count(long, !with_prefix),
-        };
-
         let colors = match self.mode {
             KeyMode::Unselected => colored_elements.unselected,
             KeyMode::UnselectedAlternate => colored_elements.unselected_alternate,
             KeyMode::Selected => colored_elements.selected,
             KeyMode::Disabled => colored_elements.disabled,
         };
+        let key_binding = match (&self.mode, &self.key) {
+            // Disabled or unreachable mode, don't print
+            (_, None) | (KeyMode::Disabled, _) => return StatusLine::default(),
+            // Reachable mode, print
+            (_, Some(_)) => self.letter_shortcut(colored_elements.notation, colors.marker, long, !with_prefix),
+        };
+        let [left_bracket, right_bracket] = colors.brackets;
+        let separator = colored_elements.fonts.separator();
         let start_separator = if !with_prefix && first_tile { "" } else { separator };
-        let prefix_separator = colors.prefix_separator.paint(start_separator);
-        let char_shortcut = colors.char_shortcut.paint(key_binding);
-        let suffix_separator = colors.suffix_separator.paint(separator);
+        let target = self.click_target();
+        let tile = |text: String, style| Span::new(text, Role::Tile, style).with_target(target.clone());
 
+        let mut spans = vec![Span::new(start_separator, Role::Separator, colors.prefix_separator)];
         if long {
-            let char_left_separator = colors.char_left_separator.paint(" <".to_string());
-            let char_right_separator = colors.char_right_separator.paint("> ".to_string());
-            let styled_text = colors.styled_text.paint(format!("{key_hint} "));
-
             // Full form printing
-            StatusLine {
-                part: ANSIStrings(&[
-                    prefix_separator,
-                    char_left_separator,
-                    char_shortcut,
-                    char_right_separator,
-                    styled_text,
-                    suffix_separator,
-                ])
-                .to_string(),
-                len: start_separator.chars().count() // Separator
-                    + 2                              // " <"
-                    + count                          // Key binding
-                    + 2                              // "> "
-                    + key_hint.chars().count()       // Key hint (mode)
-                    + 1                              // " "
-                    + separator.chars().count(),     // Separator
-            }
+            spans.extend([
+                tile(format!(" {}{left_bracket}", colors.marker), colors.char_left_separator),
+                Span::new(key_binding, Role::Key, colors.char_shortcut).with_target(target.clone()),
+                tile(format!("{right_bracket} "), colors.char_right_separator),
+                tile(format!("{key_hint} "), colors.styled_text),
+            ]);
         } else {
             // Short form printing
-            StatusLine {
-                part: ANSIStrings(&[prefix_separator, char_shortcut, suffix_separator]).to_string(),
-                len: separator.chars().count()      // Separator
-                    + count                         // Key binding
-                    + separator.chars().count(),    // Separator
-            }
+            spans.push(Span::new(key_binding, Role::Key, colors.char_shortcut).with_target(target));
         }
+        spans.push(Span::new(separator, Role::Separator, colors.suffix_separator));
+
+        StatusLine::new(spans)
     }
 }
 
-pub fn generate_shortcuts(keybinds: &[(Key, Vec<Action>)], mode: &InputMode) -> Vec<KeyShortcut> {
+pub fn generate_shortcuts(keybinds: &ModeKeybinds, mode: &InputMode) -> Vec<KeyShortcut> {
     let mut shortcuts = KeyShortcut::default_shortcuts(keybinds);
 
     let key_action = match mode {
@@ -198,7 +175,7 @@ pub fn generate_shortcuts(keybinds: &[(Key, Vec<Action>)], mode: &InputMode) ->
         if shortcut.action == key_action {
             // Highlight current mode
             shortcut.mode = KeyMode::Selected;
-            shortcut.key = utils::to_key(keybinds, &[Action::SwitchToMode(InputMode::Normal)]);
+            shortcut.key = keybinds.key(&[Action::SwitchToMode(InputMode::Normal)]);
         } else {
             // Hide all other modes
             shortcut.mode = KeyMode::Disabled;
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+use zellij_tile::prelude::actions::Action;
+use zellij_tile::prelude::*;
+
+use super::key_format;
+
+/// Policy: keys which Zellij binds by default to leave most modes, never shown as the key of an action: ' ', '\n', 'Esc'
+pub fn is_default_key(key: &Key) -> bool {
+    matches!(key, Key::Char(' ' | '\n') | Key::Esc)
+}
+
+/// Policy: key shown in the hints for an action sequence bound to `keys`, in configured order. The lowest key is
+/// preferred, except for `GoToNextTab` where `Right` is preferred, assuming `Left` is then bound to `GoToPreviousTab`.
+pub fn hint_key(actions: &[Action], keys: &[Key]) -> Option<Key> {
+    if *actions == [Action::GoToNextTab] && keys.contains(&Key::Right) {
+        return Some(Key::Right);
+    }
+
+    keys.iter().min().copied()
+}
+
+/// Policy: key shown to get back to Normal mode, among `keys` in configured order. '\n' is preferred, otherwise the
+/// first key.
+pub fn to_normal_key(keys: &[Key]) -> Option<Key> {
+    if keys.contains(&Key::Char('\n')) { Some(Key::Char('\n')) } else { keys.first().copied() }
+}
+
+/// Policy: whether the modifier of `key` bound to `actions` counts towards the superkey, i.e. whether it switches to one
+/// of the modes shown as tiles, or quits
+fn is_superkey_binding(key: &Key, actions: &[Action]) -> bool {
+    if is_default_key(key) { return false; }
+
+    match actions.first() {
+        Some(Action::SwitchToMode(mode)) => matches!(
+            mode,
+            InputMode::Normal
+                | InputMode::Locked
+                | InputMode::Pane
+                | InputMode::Tab
+                | InputMode::Resize
+                | InputMode::Move
+                | InputMode::Search
+                | InputMode::Scroll
+                | InputMode::Session
+                | InputMode::Tmux
+        ),
+        Some(Action::Quit) => true,
+        _ => false,
+    }
+}
+
+/// Action sequence keying the index. `Action` isn't `Hash`, so sequences are hashed by their variants along with the
+/// arguments which usually tell bindings of the same variant apart, and compared by value.
+#[derive(PartialEq, Eq)]
+struct ActionsKey(Vec<Action>);
+
+impl Hash for ActionsKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for action in &self.0 {
+            mem::discriminant(action).hash(state);
+            match action {
+                Action::SwitchToMode(mode) | Action::SwitchModeForAllClients(mode) => mode.hash(state),
+                Action::Resize(resize, direction) => (resize, direction).hash(state),
+                Action::MoveFocus(direction) | Action::MoveFocusOrTab(direction) => direction.hash(state),
+                Action::MovePane(direction) | Action::NewPane(direction, _) => direction.hash(state),
+                Action::GoToTab(index) => index.hash(state),
+                _ => {}
+            }
+        }
+    }
+}
+
+struct Binding {
+    // All keys bound to the action sequence, in configured order
+    keys: Vec<Key>,
+    // First key which isn't a default key
+    key: Option<Key>,
+    hint_key: Option<Key>,
+}
+
+/// Keybinds of a single mode, indexed by action sequence and by key
+#[derive(Default)]
+pub struct ModeKeybinds {
+    by_actions: HashMap<ActionsKey, Binding>,
+    by_key: HashMap<Key, Vec<Action>>,
+    superkey: Option<&'static str>,
+}
+
+impl ModeKeybinds {
+    pub fn new(keybinds: &[(Key, Vec<Action>)]) -> Self {
+        let mut keys_by_actions = HashMap::<ActionsKey, Vec<Key>>::new();
+        let mut by_key = HashMap::new();
+        for (key, actions) in keybinds {
+            keys_by_actions.entry(ActionsKey(actions.clone())).or_default().push(*key);
+            by_key.insert(*key, actions.clone());
+        }
+
+        let by_actions = keys_by_actions
+            .into_iter()
+            .map(|(actions, keys)| {
+                let binding = Binding {
+                    key: keys.iter().find(|key| !is_default_key(key)).copied(),
+                    hint_key: hint_key(&actions.0, &keys),
+                    keys,
+                };
+                (actions, binding)
+            })
+            .collect();
+
+        // The superkey is only shown if all mode switches with a modifier share the same one
+        let mut modifiers = keybinds
+            .iter()
+            .filter(|(key, actions)| is_superkey_binding(key, actions))
+            .filter_map(|(key, _)| key_format::modifier(key));
+        let superkey = match modifiers.next() {
+            Some(modifier) if modifiers.all(|other| other == modifier) => Some(modifier),
+            _ => None,
+        };
+
+        ModeKeybinds {
+            by_actions,
+            by_key,
+            superkey,
+        }
+    }
+
+    fn binding(&self, actions: &[Action]) -> Option<&Binding> {
+        self.by_actions.get(&ActionsKey(actions.to_vec()))
+    }
+
+    /// All keys bound to `actions`, in configured order
+    pub fn keys(&self, actions: &[Action]) -> &[Key] {
+        self.binding(actions).map_or(&[], |binding| &binding.keys)
+    }
+
+    /// Key bound to `actions`, skipping default keys, see `is_default_key`
+    pub fn key(&self, actions: &[Action]) -> Option<Key> {
+        self.binding(actions)?.key
+    }
+
+    /// Key shown in the hints for `actions`, see `hint_key`
+    pub fn hint_key(&self, actions: &[Action]) -> Option<Key> {
+        self.binding(actions)?.hint_key
+    }
+
+    /// Key shown to get back to Normal mode, see `to_normal_key`
+    pub fn to_normal_key(&self) -> Option<Key> {
+        to_normal_key(self.keys(&[Action::SwitchToMode(InputMode::Normal)]))
+    }
+
+    /// Actions bound to `key`, if any
+    pub fn actions(&self, key: &Key) -> Option<&[Action]> {
+        self.by_key.get(key).map(Vec::as_slice)
+    }
+
+    /// Modifier shared by all keys switching modes, if any
+    pub fn superkey(&self) -> Option<&'static str> {
+        self.superkey
+    }
+}
+
+/// Keybinds of every mode, built once whenever the keybinds change
+#[derive(Default)]
+pub struct KeybindIndex {
+    modes: HashMap<InputMode, ModeKeybinds>,
+    // Returned for modes without keybinds
+    empty: ModeKeybinds,
+}
+
+impl KeybindIndex {
+    pub fn new(mode_info: &ModeInfo) -> Self {
+        KeybindIndex {
+            modes: mode_info.keybinds.iter().map(|(mode, keybinds)| (*mode, ModeKeybinds::new(keybinds))).collect(),
+            empty: ModeKeybinds::default(),
+        }
+    }
+
+    pub fn mode(&self, mode: InputMode) -> &ModeKeybinds {
+        self.modes.get(&mode).unwrap_or(&self.empty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zellij_tile::prelude::actions::Action;
+    use zellij_tile::prelude::*;
+
+    use super::{hint_key, is_default_key, is_superkey_binding, to_normal_key, ModeKeybinds};
+
+    #[test]
+    fn default_keys() {
+        assert!(is_default_key(&Key::Char(' ')));
+        assert!(is_default_key(&Key::Char('\n')));
+        assert!(is_default_key(&Key::Esc));
+        assert!(!is_default_key(&Key::Char('q')));
+        assert!(!is_default_key(&Key::Ctrl('\n')));
+    }
+
+    #[test]
+    fn hint_key_prefers_lowest_key() {
+        let keys = [Key::Char('l'), Key::Char('k')];
+        assert_eq!(hint_key(&[Action::MoveFocus(Direction::Right)], &keys), Some(Key::Char('k')));
+        assert_eq!(hint_key(&[Action::MoveFocus(Direction::Right)], &[]), None);
+    }
+
+    #[test]
+    fn hint_key_prefers_right_for_next_tab() {
+        let keys = [Key::Char('l'), Key::Left, Key::Right];
+        assert_eq!(hint_key(&[Action::GoToNextTab], &keys), Some(Key::Right));
+        // Falls back to the lowest key without `Right`
+        assert_eq!(hint_key(&[Action::GoToNextTab], &keys[..2]), Some(Key::Left));
+        // Only for `GoToNextTab` on its own
+        assert_eq!(hint_key(&[Action::GoToNextTab, Action::SwitchToMode(InputMode::Normal)], &keys), Some(Key::Left));
+    }
+
+    #[test]
+    fn to_normal_key_prefers_enter() {
+        assert_eq!(to_normal_key(&[Key::Esc, Key::Char('\n')]), Some(Key::Char('\n')));
+        assert_eq!(to_normal_key(&[Key::Esc, Key::Ctrl('c')]), Some(Key::Esc));
+        assert_eq!(to_normal_key(&[]), None);
+    }
+
+    #[test]
+    fn superkey_bindings() {
+        assert!(is_superkey_binding(&Key::Ctrl('p'), &[Action::SwitchToMode(InputMode::Pane)]));
+        assert!(is_superkey_binding(&Key::Ctrl('q'), &[Action::Quit]));
+        assert!(!is_superkey_binding(&Key::Esc, &[Action::SwitchToMode(InputMode::Normal)]));
+        assert!(!is_superkey_binding(&Key::Char('r'), &[Action::SwitchToMode(InputMode::RenameTab)]));
+        assert!(!is_superkey_binding(&Key::Alt(CharOrArrow::Char('n')), &[Action::NewPane(None, None)]));
+    }
+
+    #[test]
+    fn superkey_is_shared_modifier() {
+        let keybinds = [
+            (Key::Ctrl('p'), vec![Action::SwitchToMode(InputMode::Pane)]),
+            (Key::Ctrl('t'), vec![Action::SwitchToMode(InputMode::Tab)]),
+            (Key::Esc, vec![Action::SwitchToMode(InputMode::Normal)]),
+            (Key::Alt(CharOrArrow::Char('n')), vec![Action::NewPane(None, None)]),
+        ];
+        assert_eq!(ModeKeybinds::new(&keybinds).superkey(), Some("Ctrl"));
+
+        let mut mixed = keybinds.to_vec();
+        mixed.push((Key::Alt(CharOrArrow::Char('s')), vec![Action::SwitchToMode(InputMode::Scroll)]));
+        assert_eq!(ModeKeybinds::new(&mixed).superkey(), None);
+
+        let unmodified = [(Key::Char('p'), vec![Action::SwitchToMode(InputMode::Pane)])];
+        assert_eq!(ModeKeybinds::new(&unmodified).superkey(), None);
+    }
+
+    #[test]
+    fn indexes_actions_by_value() {
+        let keybinds = [
+            (Key::Char('1'), vec![Action::GoToTab(1), Action::SwitchToMode(InputMode::Normal)]),
+            (Key::Char('2'), vec![Action::GoToTab(2), Action::SwitchToMode(InputMode::Normal)]),
+            (Key::Char('w'), vec![Action::WriteChars("a".to_string())]),
+            (Key::Char('x'), vec![Action::WriteChars("b".to_string())]),
+            (Key::Char('\n'), vec![Action::SwitchToMode(InputMode::Normal)]),
+            (Key::Esc, vec![Action::SwitchToMode(InputMode::Normal)]),
+        ];
+        let index = ModeKeybinds::new(&keybinds);
+
+        assert_eq!(index.keys(&[Action::GoToTab(2), Action::SwitchToMode(InputMode::Normal)]), [Key::Char('2')]);
+        // Told apart by arguments which aren't hashed
+        assert_eq!(index.key(&[Action::WriteChars("b".to_string())]), Some(Key::Char('x')));
+        assert_eq!(index.keys(&[Action::GoToTab(3)]), []);
+        // Default keys are skipped, but still listed
+        assert_eq!(index.key(&[Action::SwitchToMode(InputMode::Normal)]), None);
+        assert_eq!(index.to_normal_key(), Some(Key::Char('\n')));
+        assert_eq!(index.actions(&Key::Char('1')), Some(&keybinds[0].1[..]));
+    }
+}
@@ -1,4 +1,17 @@
+pub mod accents;
+pub mod color_depth;
 pub mod colored_elements;
-mod key_shortcut;
+pub mod config;
+pub mod contrast;
+pub mod fonts;
+pub mod key_format;
+pub mod key_shortcut;
+pub mod keybind_index;
+pub mod notification;
+pub mod render_cache;
+pub mod segments;
+pub mod span;
 pub mod status_line;
-mod utils;
+pub mod theme;
+pub mod tips;
+pub mod utils;
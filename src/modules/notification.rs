@@ -0,0 +1,282 @@
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use strum::EnumString;
+
+use super::colored_elements::{ColoredElements, SegmentLevel};
+use super::config::Config;
+
+// Root of Zellij's working directory, as seen from the plugin
+static HOST_PATH: &str = "/host";
+// Name of `CustomMessage`s carrying notifications
+pub static MESSAGE_NAME: &str = "notify";
+
+#[derive(Clone, Copy, Default, EnumString, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum Level {
+    #[default]
+    Info,
+    #[strum(serialize = "warn", serialize = "warning")]
+    Warn,
+    Error,
+}
+
+impl From<Level> for SegmentLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Info => SegmentLevel::Accent,
+            Level::Warn => SegmentLevel::Warning,
+            Level::Error => SegmentLevel::Critical,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Notification {
+    pub level: Level,
+    pub message: String,
+}
+
+impl Notification {
+    /// Parses a `[<level>: ]<message>` line, where `<level>` is one of `info`, `warn` or `error`
+    pub fn parse(line: &str) -> Option<Notification> {
+        let line = line.trim();
+        if line.is_empty() { return None; }
+
+        let (level, message) = match line.split_once(':') {
+            Some((level, message)) => match level.trim().to_lowercase().parse() {
+                Ok(level) => (level, message.trim()),
+                // Not a level, so part of the message
+                Err(_) => (Level::default(), line),
+            },
+            None => (Level::default(), line),
+        };
+        if message.is_empty() { return None; }
+
+        Some(Notification {
+            level,
+            message: message.to_string(),
+        })
+    }
+
+    pub fn style(&self, colored_elements: &ColoredElements) -> ansi_term::Style {
+        colored_elements.segment_style(self.level.into())
+    }
+}
+
+#[derive(Default)]
+pub struct Notifications {
+    inbox: Option<PathBuf>,
+    // Bytes of the inbox read so far. The inbox is left as is, as a writer could append to it while it's emptied.
+    consumed: u64,
+    duration: f64,
+    queue: VecDeque<Notification>,
+    // Currently shown notification, along with how long it has been shown for
+    current: Option<(Notification, f64)>,
+}
+
+impl Notifications {
+    pub fn new(config: &Config) -> Self {
+        let inbox = config.notification_inbox.as_ref().map(|inbox| Path::new(HOST_PATH).join(inbox));
+        // Lines written before loading were meant for an earlier session
+        let consumed = inbox.as_ref().and_then(|inbox| fs::metadata(inbox).ok()).map_or(0, |metadata| metadata.len());
+
+        Notifications {
+            inbox,
+            consumed,
+            duration: config.notification_duration,
+            queue: VecDeque::new(),
+            current: None,
+        }
+    }
+
+    pub fn watches_inbox(&self) -> bool {
+        self.inbox.is_some()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.current.is_some()
+    }
+
+    pub fn current(&self) -> Option<&Notification> {
+        self.current.as_ref().map(|(notification, _)| notification)
+    }
+
+    /// Number of notifications waiting to be shown after the current one
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn push(&mut self, payload: &str) -> bool {
+        let queued = self.queue.len();
+        self.queue.extend(payload.lines().filter_map(Notification::parse));
+        let should_render = self.queue.len() > queued;
+
+        self.advance();
+        should_render
+    }
+
+    /// Reads the lines appended to the inbox since the last read, if any of `paths` refers to it
+    pub fn read_inbox(&mut self, paths: &[PathBuf]) -> bool {
+        let Some(inbox) = &self.inbox else { return false };
+        let is_inbox = paths.iter().any(|path| path.file_name().is_some_and(|name| Some(name) == inbox.file_name()));
+        if !is_inbox { return false; }
+
+        let Some(payload) = Self::read_tail(inbox, &mut self.consumed) else { return false };
+
+        self.push(&payload)
+    }
+
+    // Reads `inbox` from `consumed` up to its last complete line, moving `consumed` past it
+    fn read_tail(inbox: &Path, consumed: &mut u64) -> Option<String> {
+        let mut file = File::open(inbox).ok()?;
+        // Start over if the inbox was emptied elsewhere
+        if file.metadata().ok()?.len() < *consumed {
+            *consumed = 0;
+        }
+
+        let mut tail = vec![];
+        file.seek(SeekFrom::Start(*consumed)).ok()?;
+        file.read_to_end(&mut tail).ok()?;
+        // A line still being written is left for later
+        let complete = tail.iter().rposition(|byte| *byte == b'\n').map_or(0, |newline| newline + 1);
+        *consumed += complete as u64;
+
+        Some(String::from_utf8_lossy(&tail[..complete]).into_owned())
+    }
+
+    pub fn tick(&mut self, secs: f64) -> bool {
+        if let Some((_, shown)) = &mut self.current {
+            *shown += secs;
+            if *shown >= self.duration {
+                self.current = None;
+                self.advance();
+                return true;
+            }
+        }
+
+        false
+    }
+
+    // Show the next queued notification, if none is shown
+    fn advance(&mut self) {
+        if self.current.is_none() {
+            self.current = self.queue.pop_front().map(|notification| (notification, 0.0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    use super::{Level, Notifications};
+    use crate::modules::config::Config;
+
+    fn notifications(duration: f64) -> Notifications {
+        Notifications::new(&Config {
+            notification_duration: duration,
+            ..Config::default()
+        })
+    }
+
+    fn inbox(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("oneliner-inbox-{name}-{}", std::process::id()))
+    }
+
+    fn current(notifications: &Notifications) -> Option<(Level, &str)> {
+        notifications.current().map(|notification| (notification.level, &notification.message[..]))
+    }
+
+    #[test]
+    fn queues_pushed_notifications() {
+        let mut notifications = notifications(5.0);
+        assert!(!notifications.is_active());
+
+        assert!(notifications.push("build finished\nwarn: 3 warnings\n\nerror:\n"));
+        assert!(current(&notifications) == Some((Level::Info, "build finished")));
+        assert_eq!(notifications.queued(), 1);
+
+        assert!(notifications.push("error: tests failed"));
+        assert!(current(&notifications) == Some((Level::Info, "build finished")));
+        assert_eq!(notifications.queued(), 2);
+
+        assert!(!notifications.push("  \n"));
+        assert_eq!(notifications.queued(), 2);
+    }
+
+    #[test]
+    fn expires_notifications_in_turn() {
+        let mut notifications = notifications(5.0);
+        notifications.push("first\nwarn: second");
+
+        assert!(!notifications.tick(4.0));
+        assert!(notifications.tick(1.0));
+        assert!(current(&notifications) == Some((Level::Warn, "second")));
+        assert_eq!(notifications.queued(), 0);
+
+        assert!(notifications.tick(5.0));
+        assert!(!notifications.is_active());
+        assert!(!notifications.tick(5.0));
+    }
+
+    #[test]
+    fn reads_only_complete_lines_appended_since_last_read() {
+        let inbox = inbox("tail");
+        fs::write(&inbox, "info: first\n").unwrap();
+        let mut consumed = 0;
+
+        assert_eq!(Notifications::read_tail(&inbox, &mut consumed).as_deref(), Some("info: first\n"));
+        assert_eq!(consumed, 12);
+
+        let mut file = OpenOptions::new().append(true).open(&inbox).unwrap();
+        write!(file, "warn: second\nerror: thi").unwrap();
+        assert_eq!(Notifications::read_tail(&inbox, &mut consumed).as_deref(), Some("warn: second\n"));
+        // Nothing but a partial line
+        assert_eq!(Notifications::read_tail(&inbox, &mut consumed).as_deref(), Some(""));
+        writeln!(file, "rd").unwrap();
+        assert_eq!(Notifications::read_tail(&inbox, &mut consumed).as_deref(), Some("error: third\n"));
+        assert_eq!(Notifications::read_tail(&inbox, &mut consumed).as_deref(), Some(""));
+
+        // Emptied elsewhere
+        fs::write(&inbox, "fourth\n").unwrap();
+        assert_eq!(Notifications::read_tail(&inbox, &mut consumed).as_deref(), Some("fourth\n"));
+        assert_eq!(consumed, 7);
+
+        fs::remove_file(&inbox).unwrap();
+        assert_eq!(Notifications::read_tail(&inbox, &mut consumed), None);
+    }
+
+    #[test]
+    fn reads_inbox_without_emptying_it() {
+        let inbox = inbox("read");
+        fs::write(&inbox, "written before loading\n").unwrap();
+        let mut notifications = Notifications {
+            inbox: Some(inbox.clone()),
+            consumed: fs::metadata(&inbox).unwrap().len(),
+            ..notifications(5.0)
+        };
+
+        let paths = [inbox.clone()];
+
+        let mut file = OpenOptions::new().append(true).open(&inbox).unwrap();
+        write!(file, "error: build failed\nwarn: ").unwrap();
+        assert!(!notifications.read_inbox(&[PathBuf::from("/host/other")]));
+        assert!(notifications.read_inbox(&paths));
+        assert!(current(&notifications) == Some((Level::Error, "build failed")));
+
+        assert!(!notifications.read_inbox(&paths));
+        writeln!(file, "retrying").unwrap();
+        assert!(notifications.read_inbox(&paths));
+        assert_eq!(notifications.queued(), 1);
+
+        let contents = fs::read_to_string(&inbox).unwrap();
+        assert_eq!(contents, "written before loading\nerror: build failed\nwarn: retrying\n");
+
+        fs::remove_file(&inbox).unwrap();
+    }
+}
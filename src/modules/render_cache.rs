@@ -0,0 +1,60 @@
+use zellij_tile::prelude::*;
+
+use super::span;
+use super::status_line::StatusLine;
+
+// Everything the rendered line depends on. Comparing keybinds is costly on large keymaps, so `revision` covers them
+// along with the plugin's own state, e.g. segments and notifications.
+struct RenderKey {
+    mode: InputMode,
+    style: Style,
+    capabilities: PluginCapabilities,
+    tabs: Vec<TabInfo>,
+    width: usize,
+    revision: u64,
+}
+
+impl RenderKey {
+    fn matches(&self, mode_info: &ModeInfo, tabs: &[TabInfo], width: usize, revision: u64) -> bool {
+        // Cheapest comparisons first
+        self.width == width
+            && self.revision == revision
+            && self.mode == mode_info.mode
+            && self.capabilities == mode_info.capabilities
+            && self.style == mode_info.style
+            && self.tabs == tabs
+    }
+}
+
+/// Keeps the last rendered line, to skip rebuilding it when nothing relevant changed
+#[derive(Default)]
+pub struct RenderCache {
+    key: Option<RenderKey>,
+    // Reused across renders, to avoid reallocating
+    output: String,
+}
+
+impl RenderCache {
+    /// Returns the cached line, rebuilding it with `render` if any of the inputs changed since.
+    /// `revision` should be bumped whenever the keybinds, or anything else shown in the line, change.
+    pub fn get_or_render(
+        &mut self, mode_info: &ModeInfo, tabs: &[TabInfo], width: usize, revision: u64, render: impl FnOnce() -> StatusLine,
+    ) -> &str {
+        let is_fresh = self.key.as_ref().is_some_and(|key| key.matches(mode_info, tabs, width, revision));
+
+        if !is_fresh {
+            self.output.clear();
+            span::push_ansi(render().spans(), &mut self.output);
+            self.key = Some(RenderKey {
+                mode: mode_info.mode,
+                style: mode_info.style,
+                capabilities: mode_info.capabilities,
+                tabs: tabs.to_vec(),
+                width,
+                revision,
+            });
+        }
+
+        &self.output
+    }
+}
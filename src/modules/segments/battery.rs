@@ -0,0 +1,336 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{segment_status, UNAVAILABLE_MARKER};
+use crate::modules::colored_elements::{ColoredElements, SegmentLevel};
+use crate::modules::config::Config;
+use crate::modules::status_line::StatusLine;
+
+// Nerd Font's `nf-fa-battery_full`
+static BATTERY_ICON: &str = " \u{f240} ";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Charge {
+    Charging,
+    Discharging,
+    Full,
+    Unknown,
+}
+
+#[derive(PartialEq)]
+struct BatteryStatus {
+    capacity: f64,
+    charge: Charge,
+    // Hours until fully charged or discharged
+    time_estimate: Option<f64>,
+}
+
+struct Battery {
+    capacity: Option<f64>,
+    status: String,
+    // Either energy (µWh, µW) or charge (µAh, µA), depending on the driver
+    now: Option<f64>,
+    full: Option<f64>,
+    rate: Option<f64>,
+}
+
+impl Battery {
+    fn read(path: &Path) -> Option<Battery> {
+        let read = |file: &str| fs::read_to_string(path.join(file)).ok().map(|value| value.trim().to_string());
+        let read_number = |files: &[&str]| files.iter().find_map(|file| read(file)?.parse::<f64>().ok());
+
+        if read("type")? != "Battery" { return None; }
+
+        Some(Battery {
+            capacity: read_number(&["capacity"]),
+            status: read("status").unwrap_or_default(),
+            now: read_number(&["energy_now", "charge_now"]),
+            full: read_number(&["energy_full", "charge_full"]),
+            rate: read_number(&["power_now", "current_now"]).map(f64::abs).filter(|rate| *rate > 0.0),
+        })
+    }
+}
+
+impl BatteryStatus {
+    /// Combines all batteries found in a `/sys/class/power_supply` tree
+    fn read(path: &Path) -> Option<BatteryStatus> {
+        let mut entries = fs::read_dir(path).ok()?.filter_map(Result::ok).map(|entry| entry.path()).collect::<Vec<PathBuf>>();
+        entries.sort();
+        let batteries = entries.iter().filter_map(|entry| Battery::read(entry)).collect::<Vec<Battery>>();
+        if batteries.is_empty() { return None; }
+
+        let sum = |field: fn(&Battery) -> Option<f64>| batteries.iter().map(field).sum::<Option<f64>>();
+        let (now, full, rate) = (sum(|b| b.now), sum(|b| b.full), sum(|b| b.rate));
+
+        let capacity = match (now, full) {
+            (Some(now), Some(full)) if full > 0.0 => 100.0 * now / full,
+            _ => {
+                let capacities = batteries.iter().filter_map(|battery| battery.capacity).collect::<Vec<f64>>();
+                if capacities.is_empty() { return None; }
+                capacities.iter().sum::<f64>() / capacities.len() as f64
+            }
+        };
+
+        let charge = batteries
+            .iter()
+            .map(|battery| match &battery.status[..] {
+                "Charging" => Charge::Charging,
+                "Discharging" => Charge::Discharging,
+                "Full" | "Not charging" => Charge::Full,
+                _ => Charge::Unknown,
+            })
+            .find(|charge| *charge != Charge::Unknown)
+            .unwrap_or(Charge::Unknown);
+
+        let time_estimate = match (charge, now, full, rate) {
+            (Charge::Discharging, Some(now), _, Some(rate)) => Some(now / rate),
+            (Charge::Charging, Some(now), Some(full), Some(rate)) => Some((full - now).max(0.0) / rate),
+            _ => None,
+        };
+
+        Some(BatteryStatus {
+            capacity: capacity.clamp(0.0, 100.0),
+            charge,
+            time_estimate,
+        })
+    }
+}
+
+pub struct BatterySegment {
+    path: PathBuf,
+    refresh_interval: f64,
+    warning_threshold: f64,
+    critical_threshold: f64,
+    elapsed: f64,
+    // Whether a battery could be read from `path` on the last refresh
+    available: bool,
+    status: Option<BatteryStatus>,
+}
+
+impl BatterySegment {
+    pub fn new(config: &Config) -> Self {
+        BatterySegment {
+            path: PathBuf::from(&config.battery_path),
+            refresh_interval: config.battery_refresh_interval,
+            warning_threshold: config.battery_warning_threshold,
+            critical_threshold: config.battery_critical_threshold,
+            elapsed: 0.0,
+            available: true,
+            status: None,
+        }
+    }
+
+    pub fn refresh(&mut self) -> bool {
+        self.elapsed = 0.0;
+
+        let status = BatteryStatus::read(&self.path);
+        let available = status.is_some();
+        if self.available && !available {
+            eprintln!("oneliner: no battery found in {}, is `battery_path` reachable from the plugin?", self.path.display());
+        }
+        let should_render = self.status != status || self.available != available;
+        self.status = status;
+        self.available = available;
+
+        should_render
+    }
+
+    pub fn tick(&mut self, secs: f64) -> bool {
+        self.elapsed += secs;
+        if self.elapsed < self.refresh_interval { return false; }
+
+        self.refresh()
+    }
+
+    fn level(&self, status: &BatteryStatus) -> SegmentLevel {
+        match status.charge {
+            // Nothing to worry about while plugged in
+            Charge::Charging | Charge::Full => SegmentLevel::Accent,
+            _ if status.capacity <= self.critical_threshold => SegmentLevel::Critical,
+            _ if status.capacity <= self.warning_threshold => SegmentLevel::Warning,
+            _ => SegmentLevel::Normal,
+        }
+    }
+
+    pub fn status_line(&self, colored_elements: &ColoredElements) -> StatusLine {
+        let fonts = colored_elements.fonts;
+        let label = if fonts.has_icons() { BATTERY_ICON } else { " bat " };
+        let Some(status) = &self.status else {
+            if self.available { return StatusLine::default(); }
+
+            return segment_status(&[
+                colored_elements.segment.paint(label),
+                colored_elements.segment_warning.paint(UNAVAILABLE_MARKER),
+                colored_elements.segment.paint(" "),
+            ]);
+        };
+
+        let charge_marker = match status.charge {
+            Charge::Charging => fonts.glyph("↑", "+"),
+            Charge::Discharging => fonts.glyph("↓", "-"),
+            Charge::Full | Charge::Unknown => "",
+        };
+
+        let mut bits = vec![colored_elements.segment.paint(label)];
+        bits.push(colored_elements.segment_style(self.level(status)).paint(format!("{:.0}%{charge_marker}", status.capacity)));
+        if let Some(hours) = status.time_estimate {
+            let minutes = (hours * 60.0).round() as u64;
+            bits.push(colored_elements.segment.paint(format!(" {}:{:02}", minutes / 60, minutes % 60)));
+        }
+        bits.push(colored_elements.segment.paint(" "));
+
+        segment_status(&bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use super::{BatterySegment, BatteryStatus, Charge};
+    use crate::modules::colored_elements::{ColoredElements, SegmentLevel};
+    use crate::modules::config::Config;
+    use crate::modules::fonts::FontProfile;
+
+    /// Power-supply tree in a temporary directory, removed when dropped
+    struct Fixture(PathBuf);
+
+    impl Fixture {
+        fn new(name: &str, supplies: &[(&str, &[(&str, &str)])]) -> Fixture {
+            let path = std::env::temp_dir().join(format!("oneliner-power-supply-{name}-{}", std::process::id()));
+            for (supply, files) in supplies {
+                fs::create_dir_all(path.join(supply)).unwrap();
+                for (file, value) in *files {
+                    fs::write(path.join(supply).join(file), format!("{value}\n")).unwrap();
+                }
+            }
+
+            Fixture(path)
+        }
+
+        fn read(&self) -> Option<BatteryStatus> {
+            BatteryStatus::read(&self.0)
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn assert_status(status: Option<BatteryStatus>, capacity: f64, charge: Charge, hours: Option<f64>) {
+        let status = status.expect("a battery");
+        assert!((status.capacity - capacity).abs() < 0.01, "capacity {}", status.capacity);
+        assert!(status.charge == charge);
+        match (status.time_estimate, hours) {
+            (Some(estimate), Some(hours)) => assert!((estimate - hours).abs() < 0.01, "estimate {estimate}"),
+            (estimate, hours) => assert!(estimate.is_none() && hours.is_none(), "estimate {estimate:?}"),
+        }
+    }
+
+    #[test]
+    fn reads_energy() {
+        let fixture = Fixture::new("energy", &[(
+            "BAT0",
+            &[("type", "Battery"), ("status", "Discharging"), ("energy_now", "30000000"), ("energy_full", "60000000"),
+                ("power_now", "15000000"), ("capacity", "12")],
+        )]);
+
+        // Energy takes precedence over the rounded capacity
+        assert_status(fixture.read(), 50.0, Charge::Discharging, Some(2.0));
+    }
+
+    #[test]
+    fn falls_back_to_charge() {
+        let fixture = Fixture::new("charge", &[(
+            "BAT0",
+            &[("type", "Battery"), ("status", "Charging"), ("charge_now", "1000000"), ("charge_full", "4000000"),
+                ("current_now", "-1500000"), ("capacity", "12")],
+        )]);
+
+        // Some drivers report the current as negative
+        assert_status(fixture.read(), 25.0, Charge::Charging, Some(2.0));
+    }
+
+    #[test]
+    fn falls_back_to_capacity() {
+        let fixture = Fixture::new("capacity", &[
+            ("BAT0", &[("type", "Battery"), ("status", "Full"), ("capacity", "97")]),
+            ("BAT1", &[("type", "Battery"), ("status", "Not charging"), ("capacity", "89")]),
+        ]);
+
+        assert_status(fixture.read(), 93.0, Charge::Full, None);
+    }
+
+    #[test]
+    fn combines_batteries_and_skips_other_supplies() {
+        let fixture = Fixture::new("combined", &[
+            ("AC", &[("type", "Mains"), ("online", "1")]),
+            ("BAT0", &[("type", "Battery"), ("status", "Unknown"), ("energy_now", "10000000"), ("energy_full", "20000000")]),
+            ("BAT1", &[("type", "Battery"), ("status", "Discharging"), ("energy_now", "20000000"),
+                ("energy_full", "20000000"), ("power_now", "10000000")]),
+        ]);
+
+        // Without the rate of every battery, no estimate is made
+        assert_status(fixture.read(), 75.0, Charge::Discharging, None);
+    }
+
+    #[test]
+    fn estimates_nothing_without_rate() {
+        let fixture = Fixture::new("no-rate", &[(
+            "BAT0",
+            &[("type", "Battery"), ("status", "Discharging"), ("energy_now", "30000000"), ("energy_full", "60000000"),
+                ("power_now", "0")],
+        )]);
+
+        assert_status(fixture.read(), 50.0, Charge::Discharging, None);
+    }
+
+    #[test]
+    fn finds_no_battery() {
+        assert!(Fixture::new("mains", &[("AC", &[("type", "Mains")])]).read().is_none());
+        assert!(Fixture::new("unreadable", &[("BAT0", &[("type", "Battery"), ("capacity", "lots")])]).read().is_none());
+        assert!(BatteryStatus::read(Path::new("/nonexistent/power_supply")).is_none());
+    }
+
+    #[test]
+    fn levels_follow_thresholds_while_discharging() {
+        let segment = BatterySegment::new(&Config {
+            battery_warning_threshold: 30.0,
+            battery_critical_threshold: 10.0,
+            ..Config::default()
+        });
+        let level = |capacity: f64, charge: Charge| segment.level(&BatteryStatus { capacity, charge, time_estimate: None });
+
+        assert!(level(50.0, Charge::Discharging) == SegmentLevel::Normal);
+        assert!(level(30.0, Charge::Discharging) == SegmentLevel::Warning);
+        assert!(level(10.0, Charge::Discharging) == SegmentLevel::Critical);
+        assert!(level(5.0, Charge::Unknown) == SegmentLevel::Critical);
+        assert!(level(5.0, Charge::Charging) == SegmentLevel::Accent);
+        assert!(level(100.0, Charge::Full) == SegmentLevel::Accent);
+    }
+
+    #[test]
+    fn renders_fixture() {
+        let fixture = Fixture::new("render", &[(
+            "BAT0",
+            &[("type", "Battery"), ("status", "Discharging"), ("energy_now", "30000000"), ("energy_full", "60000000"),
+                ("power_now", "12000000")],
+        )]);
+        let mut segment = BatterySegment::new(&Config {
+            battery_path: fixture.0.display().to_string(),
+            ..Config::default()
+        });
+        let colored_elements = ColoredElements::color_elements(&Default::default(), FontProfile::Ascii);
+
+        assert!(segment.refresh());
+        assert_eq!(segment.status_line(&colored_elements).to_plain(), " bat 50%- 2:30 ");
+        assert!(!segment.refresh());
+
+        drop(fixture);
+        assert!(segment.refresh());
+        assert_eq!(segment.status_line(&colored_elements).to_plain(), " bat n/a ");
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use super::job::Job;
+use super::{segment_status, CONTEXT_KEY};
+use crate::modules::colored_elements::ColoredElements;
+use crate::modules::config::CommandConfig;
+use crate::modules::status_line::StatusLine;
+
+static CONTEXT_VALUE: &str = "command";
+static CONTEXT_NAME_KEY: &str = "oneliner_command_name";
+static STALE_MARKER: &str = "~";
+static ERROR_MARKER: &str = "!";
+
+#[derive(Clone, Copy, PartialEq)]
+enum CommandState {
+    Fresh,
+    // The last run exceeded its timeout
+    Stale,
+    // The last run exited with an error
+    Failed,
+}
+
+pub struct CommandSegment {
+    config: CommandConfig,
+    elapsed: f64,
+    job: Job,
+    output: Option<String>,
+    state: CommandState,
+}
+
+impl CommandSegment {
+    pub fn new(config: CommandConfig) -> Self {
+        CommandSegment {
+            job: Job::new(config.timeout),
+            config,
+            elapsed: 0.0,
+            output: None,
+            state: CommandState::Fresh,
+        }
+    }
+
+    pub fn is_result(&self, context: &BTreeMap<String, String>) -> bool {
+        context.get(CONTEXT_KEY).is_some_and(|value| value == CONTEXT_VALUE)
+            && context.get(CONTEXT_NAME_KEY).is_some_and(|name| *name == self.config.name)
+    }
+
+    pub fn refresh(&mut self) {
+        let context = BTreeMap::from([
+            (CONTEXT_KEY.to_string(), CONTEXT_VALUE.to_string()),
+            (CONTEXT_NAME_KEY.to_string(), self.config.name.clone()),
+        ]);
+
+        let mut cmd = vec![&self.config.command[..]];
+        cmd.extend(self.config.args.iter().map(|arg| &arg[..]));
+        if self.job.run(&cmd, context) {
+            self.elapsed = 0.0;
+        }
+    }
+
+    pub fn tick(&mut self, secs: f64) -> bool {
+        self.elapsed += secs;
+
+        if self.job.tick(secs) {
+            // Keep showing the last result as stale
+            let should_render = self.state != CommandState::Stale;
+            self.state = CommandState::Stale;
+            return should_render;
+        }
+        if self.job.is_running() { return false; }
+
+        if self.elapsed >= self.config.refresh_interval {
+            self.refresh();
+        }
+
+        false
+    }
+
+    pub fn update(&mut self, exit_code: Option<i32>, stdout: &[u8], context: &BTreeMap<String, String>) -> bool {
+        if !self.job.finish(context) { return false; }
+
+        let (output, state) = match exit_code {
+            Some(0) => {
+                // Only the first line fits in the bar
+                let stdout = String::from_utf8_lossy(stdout);
+                let output = stdout.lines().next().unwrap_or_default().trim().to_string();
+                (Some(output), CommandState::Fresh)
+            }
+            // Keep the last successful output around
+            _ => (self.output.clone(), CommandState::Failed),
+        };
+        let should_render = self.output != output || self.state != state;
+        self.output = output;
+        self.state = state;
+
+        should_render
+    }
+
+    fn truncated_output(&self, ellipsis: &str) -> Option<String> {
+        let output = self.output.as_ref().filter(|output| !output.is_empty())?;
+
+        match self.config.max_width {
+            Some(max_width) if output.chars().count() > max_width => {
+                let mut truncated = output.chars().take(max_width.saturating_sub(1)).collect::<String>();
+                truncated.push_str(ellipsis);
+                Some(truncated)
+            }
+            _ => Some(output.clone()),
+        }
+    }
+
+    pub fn status_line(&self, colored_elements: &ColoredElements) -> StatusLine {
+        let output = self.truncated_output(colored_elements.fonts.ellipsis());
+        let marker = match self.state {
+            CommandState::Fresh => None,
+            CommandState::Stale => Some(colored_elements.segment_warning.paint(STALE_MARKER)),
+            CommandState::Failed => Some(colored_elements.segment_critical.paint(ERROR_MARKER)),
+        };
+
+        let mut bits = vec![colored_elements.segment.paint(" ")];
+        match (output, marker) {
+            // Nothing to show yet
+            (None, None) => return StatusLine::default(),
+            // No output to show, so show which command is failing
+            (None, Some(marker)) => {
+                bits.push(colored_elements.segment.paint(self.config.name.clone()));
+                bits.push(colored_elements.segment.paint(" "));
+                bits.push(marker);
+            }
+            (Some(output), marker) => {
+                bits.push(colored_elements.segment_style(self.config.style).paint(output));
+                if let Some(marker) = marker {
+                    bits.push(colored_elements.segment.paint(" "));
+                    bits.push(marker);
+                }
+            }
+        }
+        bits.push(colored_elements.segment.paint(" "));
+
+        segment_status(&bits)
+    }
+}
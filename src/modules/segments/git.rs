@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+
+use zellij_tile::prelude::*;
+
+use super::job::Job;
+use super::{segment_status, CONTEXT_KEY};
+use crate::modules::colored_elements::ColoredElements;
+use crate::modules::config::Config;
+use crate::modules::status_line::StatusLine;
+
+static CONTEXT_VALUE: &str = "git";
+static GIT_STATUS: [&str; 4] = ["git", "status", "--porcelain=v2", "--branch"];
+static DIRTY_MARKER: &str = "*";
+// Powerline branch symbol, shipped with Nerd Fonts
+static BRANCH_ICON: &str = "\u{e0a0} ";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct GitStatus {
+    pub branch: String,
+    pub ahead: usize,
+    pub behind: usize,
+    pub dirty: bool,
+}
+
+impl GitStatus {
+    /// Parses the output of `git status --porcelain=v2 --branch`
+    pub fn parse(output: &str) -> Option<GitStatus> {
+        let mut status = GitStatus::default();
+        let mut oid = None;
+
+        for line in output.lines() {
+            match line.split_once(' ') {
+                Some(("#", header)) => match header.split_once(' ') {
+                    Some(("branch.oid", commit)) => oid = Some(commit),
+                    Some(("branch.head", head)) => status.branch = head.to_string(),
+                    Some(("branch.ab", ahead_behind)) => {
+                        for count in ahead_behind.split_whitespace() {
+                            if let Some(ahead) = count.strip_prefix('+') {
+                                status.ahead = ahead.parse().unwrap_or_default();
+                            } else if let Some(behind) = count.strip_prefix('-') {
+                                status.behind = behind.parse().unwrap_or_default();
+                            }
+                        }
+                    }
+                    _ => {}
+                },
+                // Changed, renamed/copied, unmerged or untracked entries
+                Some(("1" | "2" | "u" | "?", _)) => status.dirty = true,
+                _ => {}
+            }
+        }
+
+        if status.branch.is_empty() {
+            // Not the output we are looking for
+            return None;
+        }
+
+        if status.branch == "(detached)" {
+            // Show the abbreviated commit instead on a detached HEAD
+            if let Some(commit) = oid.filter(|commit| *commit != "(initial)") {
+                status.branch = commit.chars().take(7).collect();
+            }
+        }
+
+        Some(status)
+    }
+}
+
+/// Working directory shown in a pane's title, which most shells set to something like `user@host: ~/src`
+fn title_directory(title: &str) -> Option<&str> {
+    title
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .rev()
+        .map(|word| word.trim_end_matches(['$', '#', '%']))
+        .find(|word| word.starts_with('/') || *word == "~" || word.starts_with("~/"))
+}
+
+/// Command printing the git status of `directory`, Zellij's working directory by default
+fn git_command(directory: Option<&str>) -> Vec<String> {
+    let (git, args) = (GIT_STATUS[0], &GIT_STATUS[1..]);
+    match directory {
+        // Home is only known on the host, and the directory is passed as an argument to keep it away from the shell
+        Some(directory) if directory.starts_with('~') => {
+            let relative = directory.trim_start_matches('~').trim_start_matches('/');
+            let script = format!("cd \"$HOME/$1\" && exec {}", GIT_STATUS.join(" "));
+            vec!["sh", "-c", &script, "sh", relative].into_iter().map(String::from).collect()
+        }
+        Some(directory) => [git, "-C", directory].iter().chain(args).map(|arg| arg.to_string()).collect(),
+        None => GIT_STATUS.iter().map(|arg| arg.to_string()).collect(),
+    }
+}
+
+pub struct GitSegment {
+    directory: Option<String>,
+    refresh_interval: f64,
+    elapsed: f64,
+    job: Job,
+    // Id of the focused terminal pane, and the working directory shown in its title
+    focused_pane: Option<u32>,
+    pane_directory: Option<String>,
+    status: Option<GitStatus>,
+}
+
+impl GitSegment {
+    pub fn new(config: &Config) -> Self {
+        GitSegment {
+            directory: config.git_directory.clone(),
+            refresh_interval: config.git_refresh_interval,
+            elapsed: 0.0,
+            job: Job::new(config.git_timeout),
+            focused_pane: None,
+            pane_directory: None,
+            status: None,
+        }
+    }
+
+    pub fn is_result(context: &BTreeMap<String, String>) -> bool {
+        context.get(CONTEXT_KEY).is_some_and(|value| value == CONTEXT_VALUE)
+    }
+
+    pub fn refresh(&mut self) {
+        // The configured directory takes precedence over the focused pane's
+        let directory = self.directory.as_deref().or(self.pane_directory.as_deref());
+        let cmd = git_command(directory);
+        let cmd = cmd.iter().map(|arg| &arg[..]).collect::<Vec<&str>>();
+
+        let context = BTreeMap::from([(CONTEXT_KEY.to_string(), CONTEXT_VALUE.to_string())]);
+        if self.job.run(&cmd, context) {
+            self.elapsed = 0.0;
+        }
+    }
+
+    /// Follows the repository of the focused terminal pane, refreshing on the next tick when focus moves
+    pub fn focus(&mut self, pane: Option<&PaneInfo>) {
+        let directory = pane.and_then(|pane| title_directory(&pane.title)).map(ToString::to_string);
+        let focused_pane = pane.map(|pane| pane.id);
+        if focused_pane == self.focused_pane && directory == self.pane_directory { return; }
+
+        self.focused_pane = focused_pane;
+        self.pane_directory = directory;
+        // The running status is the previous pane's
+        self.job.cancel();
+        self.elapsed = self.refresh_interval;
+    }
+
+    pub fn tick(&mut self, secs: f64) {
+        self.elapsed += secs;
+
+        // A hung run is given up on, so the next refresh can go ahead
+        self.job.tick(secs);
+        if !self.job.is_running() && self.elapsed >= self.refresh_interval {
+            self.refresh();
+        }
+    }
+
+    pub fn update(&mut self, exit_code: Option<i32>, stdout: &[u8], context: &BTreeMap<String, String>) -> bool {
+        if !self.job.finish(context) { return false; }
+
+        let status = match exit_code {
+            Some(0) => GitStatus::parse(&String::from_utf8_lossy(stdout)),
+            // Not a repository, or git is unavailable
+            _ => None,
+        };
+        let should_render = self.status != status;
+        self.status = status;
+
+        should_render
+    }
+
+    pub fn status_line(&self, colored_elements: &ColoredElements) -> StatusLine {
+        let Some(status) = &self.status else { return StatusLine::default() };
+
+        let fonts = colored_elements.fonts;
+        let mut bits = vec![colored_elements.segment.paint(" ")];
+        if fonts.has_icons() {
+            bits.push(colored_elements.segment.paint(BRANCH_ICON));
+        }
+        bits.push(colored_elements.segment_accent.paint(status.branch.clone()));
+        if status.ahead > 0 {
+            bits.push(colored_elements.segment.paint(format!(" {}{}", fonts.glyph("↑", "+"), status.ahead)));
+        }
+        if status.behind > 0 {
+            bits.push(colored_elements.segment.paint(format!(" {}{}", fonts.glyph("↓", "-"), status.behind)));
+        }
+        if status.dirty {
+            bits.push(colored_elements.segment_warning.paint(format!(" {DIRTY_MARKER}")));
+        }
+        bits.push(colored_elements.segment.paint(" "));
+
+        segment_status(&bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{git_command, title_directory, GitStatus};
+
+    static OID: &str = "# branch.oid 3f9c2a1e7b5d4c6a8e0f1b2c3d4e5f6a7b8c9d0e";
+
+    fn status(branch: &str, ahead: usize, behind: usize, dirty: bool) -> Option<GitStatus> {
+        Some(GitStatus { branch: branch.to_string(), ahead, behind, dirty })
+    }
+
+    #[test]
+    fn parses_clean_branch() {
+        let output = format!("{OID}\n# branch.head main\n# branch.upstream origin/main\n# branch.ab +0 -0\n");
+        assert_eq!(GitStatus::parse(&output), status("main", 0, 0, false));
+    }
+
+    #[test]
+    fn parses_branch_without_upstream() {
+        let output = format!("{OID}\n# branch.head feature/segments\n");
+        assert_eq!(GitStatus::parse(&output), status("feature/segments", 0, 0, false));
+    }
+
+    #[test]
+    fn parses_ahead_and_behind_counts() {
+        let output = format!("{OID}\n# branch.head main\n# branch.upstream origin/main\n# branch.ab +3 -12\n");
+        assert_eq!(GitStatus::parse(&output), status("main", 3, 12, false));
+    }
+
+    #[test]
+    fn shows_abbreviated_commit_on_detached_head() {
+        let output = format!("{OID}\n# branch.head (detached)\n");
+        assert_eq!(GitStatus::parse(&output), status("3f9c2a1", 0, 0, false));
+    }
+
+    #[test]
+    fn parses_initial_commit() {
+        let output = "# branch.oid (initial)\n# branch.head main\n? README.md\n";
+        assert_eq!(GitStatus::parse(output), status("main", 0, 0, true));
+    }
+
+    #[test]
+    fn keeps_detached_marker_without_commit() {
+        let output = "# branch.oid (initial)\n# branch.head (detached)\n";
+        assert_eq!(GitStatus::parse(output), status("(detached)", 0, 0, false));
+    }
+
+    #[test]
+    fn untracked_files_are_dirty() {
+        let output = format!("{OID}\n# branch.head main\n? notes.txt\n? target/\n");
+        assert_eq!(GitStatus::parse(&output), status("main", 0, 0, true));
+    }
+
+    #[test]
+    fn changed_renamed_and_unmerged_entries_are_dirty() {
+        let entries = [
+            "1 .M N... 100644 100644 100644 0123456789abcdef0123456789abcdef01234567 0123456789abcdef0123456789abcdef01234567 src/lib.rs",
+            "2 R. N... 100644 100644 100644 0123456789abcdef0123456789abcdef01234567 0123456789abcdef0123456789abcdef01234567 R100 new.rs\told.rs",
+            "u UU N... 100644 100644 100644 100644 0123456789abcdef0123456789abcdef01234567 0123456789abcdef0123456789abcdef01234567 0123456789abcdef0123456789abcdef01234567 Cargo.toml",
+        ];
+        for entry in entries {
+            let output = format!("{OID}\n# branch.head main\n# branch.ab +1 -0\n{entry}\n");
+            assert_eq!(GitStatus::parse(&output), status("main", 1, 0, true), "{entry}");
+        }
+    }
+
+    #[test]
+    fn ignores_ignored_entries() {
+        let output = format!("{OID}\n# branch.head main\n! target/\n");
+        assert_eq!(GitStatus::parse(&output), status("main", 0, 0, false));
+    }
+
+    #[test]
+    fn rejects_other_output() {
+        assert_eq!(GitStatus::parse(""), None);
+        assert_eq!(GitStatus::parse("fatal: not a git repository (or any of the parent directories): .git\n"), None);
+    }
+
+    #[test]
+    fn reads_directory_from_pane_title() {
+        assert_eq!(title_directory("njhlai@host: ~/src/oneliner"), Some("~/src/oneliner"));
+        assert_eq!(title_directory("/etc"), Some("/etc"));
+        assert_eq!(title_directory("~"), Some("~"));
+        assert_eq!(title_directory("root@host:/var/log#"), Some("/var/log"));
+        assert_eq!(title_directory("root@host: /var/log#"), Some("/var/log"));
+        assert_eq!(title_directory("Pane #1"), None);
+        assert_eq!(title_directory("vim ~user/notes"), None);
+        assert_eq!(title_directory(""), None);
+    }
+
+    #[test]
+    fn runs_git_status_in_directory() {
+        assert_eq!(git_command(None), ["git", "status", "--porcelain=v2", "--branch"]);
+        assert_eq!(git_command(Some("/srv/repo")), ["git", "-C", "/srv/repo", "status", "--porcelain=v2", "--branch"]);
+        // Home is expanded by the shell, without the directory ever being part of the script
+        let script = "cd \"$HOME/$1\" && exec git status --porcelain=v2 --branch";
+        assert_eq!(git_command(Some("~/src/$(reboot)")), ["sh", "-c", script, "sh", "src/$(reboot)"]);
+        assert_eq!(git_command(Some("~")), ["sh", "-c", script, "sh", ""]);
+    }
+}
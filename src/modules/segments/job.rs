@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+
+use zellij_tile::prelude::*;
+
+static CONTEXT_GENERATION_KEY: &str = "oneliner_generation";
+
+/// Command run by a segment on the host, one at a time, and given up on once it exceeds its timeout
+pub struct Job {
+    timeout: f64,
+    // Seconds since the current run was issued
+    running: Option<f64>,
+    // Identifies the latest run, so late results of timed-out runs can be ignored
+    generation: usize,
+}
+
+impl Job {
+    pub fn new(timeout: f64) -> Self {
+        Job {
+            timeout,
+            running: None,
+            generation: 0,
+        }
+    }
+
+    /// Runs `cmd`, with `context` identifying the segment, unless the previous run is still going
+    pub fn run(&mut self, cmd: &[&str], mut context: BTreeMap<String, String>) -> bool {
+        // Don't pile up commands if the previous one is still running
+        if self.running.is_some() { return false; }
+
+        self.generation += 1;
+        context.insert(CONTEXT_GENERATION_KEY.to_string(), self.generation.to_string());
+        run_command(cmd, context);
+        self.running = Some(0.0);
+
+        true
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Gives up on the current run, whose result will be ignored
+    pub fn cancel(&mut self) {
+        self.running = None;
+    }
+
+    /// Advances the current run by `secs`, true when it's given up on for exceeding the timeout
+    pub fn tick(&mut self, secs: f64) -> bool {
+        let Some(running) = &mut self.running else { return false };
+        *running += secs;
+        if *running < self.timeout { return false; }
+
+        self.running = None;
+        true
+    }
+
+    /// Whether `context` comes with the result of the current run, which is then over
+    pub fn finish(&mut self, context: &BTreeMap<String, String>) -> bool {
+        let is_current = self.running.is_some()
+            && context.get(CONTEXT_GENERATION_KEY).is_some_and(|generation| *generation == self.generation.to_string());
+        if is_current {
+            self.running = None;
+        }
+
+        is_current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{Job, CONTEXT_GENERATION_KEY};
+
+    // Stands for `Job::run`, which needs the host
+    fn started(generation: usize) -> Job {
+        Job {
+            timeout: 5.0,
+            running: Some(0.0),
+            generation,
+        }
+    }
+
+    fn context(generation: usize) -> BTreeMap<String, String> {
+        BTreeMap::from([(CONTEXT_GENERATION_KEY.to_string(), generation.to_string())])
+    }
+
+    #[test]
+    fn gives_up_after_timeout() {
+        let mut job = started(1);
+
+        assert!(!job.tick(4.0));
+        assert!(job.is_running());
+        assert!(job.tick(1.0));
+        assert!(!job.is_running());
+        assert!(!job.tick(1.0));
+    }
+
+    #[test]
+    fn finishes_with_latest_result_only() {
+        let mut job = started(2);
+
+        assert!(!job.finish(&context(1)));
+        assert!(!job.finish(&BTreeMap::new()));
+        assert!(job.is_running());
+        assert!(job.finish(&context(2)));
+        assert!(!job.is_running());
+        // The same result again, or one arriving after giving up
+        assert!(!job.finish(&context(2)));
+    }
+
+    #[test]
+    fn ignores_results_of_cancelled_runs() {
+        let mut job = started(1);
+
+        job.cancel();
+        assert!(!job.finish(&context(1)));
+    }
+}
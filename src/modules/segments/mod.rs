@@ -0,0 +1,124 @@
+pub mod battery;
+pub mod command;
+pub mod git;
+mod job;
+pub mod system;
+
+use std::collections::BTreeMap;
+
+use ansi_term::ANSIString;
+use zellij_tile::prelude::*;
+
+use self::battery::BatterySegment;
+use self::command::CommandSegment;
+use self::git::GitSegment;
+use self::system::SystemSegment;
+use super::colored_elements::ColoredElements;
+use super::config::Config;
+use super::span::Span;
+use super::status_line::StatusLine;
+
+// Key identifying which segment issued a `run_command`, stored in the command context
+pub static CONTEXT_KEY: &str = "oneliner_segment";
+// Shown in place of the values of a segment whose source can't be read
+pub static UNAVAILABLE_MARKER: &str = "n/a";
+
+pub fn segment_status(bits: &[ANSIString<'static>]) -> StatusLine {
+    StatusLine::new(bits.iter().map(Span::from).collect())
+}
+
+#[derive(Default)]
+pub struct Segments {
+    git: Option<GitSegment>,
+    commands: Vec<CommandSegment>,
+    system: Option<SystemSegment>,
+    battery: Option<BatterySegment>,
+}
+
+impl Segments {
+    pub fn new(config: &Config) -> Self {
+        Segments {
+            git: config.git_segment.then(|| GitSegment::new(config)),
+            commands: config.commands.iter().cloned().map(CommandSegment::new).collect(),
+            system: config.system_segment.then(|| SystemSegment::new(config)),
+            battery: config.battery_segment.then(|| BatterySegment::new(config)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.git.is_none() && self.commands.is_empty() && self.system.is_none() && self.battery.is_none()
+    }
+
+    pub fn runs_commands(&self) -> bool {
+        self.git.is_some() || !self.commands.is_empty()
+    }
+
+    pub fn refresh(&mut self) {
+        if let Some(git) = &mut self.git {
+            git.refresh();
+        }
+        for command in &mut self.commands {
+            command.refresh();
+        }
+        if let Some(system) = &mut self.system {
+            system.refresh();
+        }
+        if let Some(battery) = &mut self.battery {
+            battery.refresh();
+        }
+    }
+
+    /// Follows the focused terminal pane, for the segments showing its state
+    pub fn focus(&mut self, pane: Option<&PaneInfo>) {
+        if let Some(git) = &mut self.git {
+            git.focus(pane);
+        }
+    }
+
+    pub fn tick(&mut self, secs: f64) -> bool {
+        let mut should_render = false;
+
+        if let Some(git) = &mut self.git {
+            git.tick(secs);
+        }
+        for command in &mut self.commands {
+            should_render |= command.tick(secs);
+        }
+        if let Some(system) = &mut self.system {
+            should_render |= system.tick(secs);
+        }
+        if let Some(battery) = &mut self.battery {
+            should_render |= battery.tick(secs);
+        }
+
+        should_render
+    }
+
+    pub fn update(&mut self, exit_code: Option<i32>, stdout: &[u8], context: &BTreeMap<String, String>) -> bool {
+        if let Some(git) = self.git.as_mut().filter(|_| GitSegment::is_result(context)) {
+            return git.update(exit_code, stdout, context);
+        }
+
+        match self.commands.iter_mut().find(|command| command.is_result(context)) {
+            Some(command) => command.update(exit_code, stdout, context),
+            None => false,
+        }
+    }
+
+    pub fn status_lines(&self, colored_elements: &ColoredElements) -> Vec<StatusLine> {
+        let mut status_lines = vec![];
+
+        if let Some(git) = &self.git {
+            status_lines.push(git.status_line(colored_elements));
+        }
+        status_lines.extend(self.commands.iter().map(|command| command.status_line(colored_elements)));
+        if let Some(system) = &self.system {
+            status_lines.push(system.status_line(colored_elements));
+        }
+        if let Some(battery) = &self.battery {
+            status_lines.push(battery.status_line(colored_elements));
+        }
+
+        status_lines
+    }
+}
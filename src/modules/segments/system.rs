@@ -0,0 +1,312 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+
+use strum::EnumString;
+
+use super::{segment_status, UNAVAILABLE_MARKER};
+use crate::modules::colored_elements::{ColoredElements, SegmentLevel};
+use crate::modules::config::Config;
+use crate::modules::fonts::FontProfile;
+use crate::modules::status_line::StatusLine;
+
+static SPARKLINE_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+static SPARKLINE_ASCII: [char; 8] = ['_', '.', ':', '-', '=', '+', '*', '#'];
+static SPARKLINE_LENGTH: usize = 8;
+
+#[derive(Clone, Copy, Default, EnumString, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum Gauge {
+    #[default]
+    Numbers,
+    Sparkline,
+}
+
+#[derive(Clone, Copy)]
+struct CpuTimes {
+    idle: u64,
+    total: u64,
+}
+
+impl CpuTimes {
+    /// Parses the aggregated `cpu` line of `/proc/stat`
+    fn parse(stat: &str) -> Option<CpuTimes> {
+        let mut fields = stat.lines().next()?.split_whitespace();
+        if fields.next()? != "cpu" { return None; }
+
+        let times = fields.filter_map(|field| field.parse::<u64>().ok()).collect::<Vec<u64>>();
+        // user, nice, system, idle and iowait are always present
+        if times.len() < 5 { return None; }
+
+        Some(CpuTimes {
+            idle: times[3] + times[4],
+            total: times.iter().sum(),
+        })
+    }
+
+    /// Percentage of time spent busy since `previous`
+    fn usage_since(&self, previous: &CpuTimes) -> Option<f64> {
+        let total = self.total.checked_sub(previous.total).filter(|total| *total > 0)?;
+        let idle = self.idle.saturating_sub(previous.idle);
+
+        Some(100.0 * (total.saturating_sub(idle)) as f64 / total as f64)
+    }
+}
+
+fn parse_cpu_count(stat: &str) -> usize {
+    stat.lines()
+        .filter(|line| line.strip_prefix("cpu").is_some_and(|cpu| cpu.starts_with(|c: char| c.is_ascii_digit())))
+        .count()
+        .max(1)
+}
+
+fn parse_load(loadavg: &str) -> Option<f64> {
+    loadavg.split_whitespace().next()?.parse().ok()
+}
+
+/// Percentage of memory in use, from `/proc/meminfo`
+fn parse_memory_usage(meminfo: &str) -> Option<f64> {
+    let field = |name: &str| {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|value| value.split_whitespace().next()?.parse::<u64>().ok())
+    };
+    let total = field("MemTotal").filter(|total| *total > 0)?;
+    let available = field("MemAvailable")?;
+
+    Some(100.0 * total.saturating_sub(available) as f64 / total as f64)
+}
+
+pub struct SystemSegment {
+    proc_path: PathBuf,
+    gauge: Gauge,
+    refresh_interval: f64,
+    warning_threshold: f64,
+    critical_threshold: f64,
+    elapsed: f64,
+    // Whether anything could be read and parsed from `proc_path` on the last refresh
+    available: bool,
+    cpu_count: usize,
+    cpu_times: Option<CpuTimes>,
+    cpu_history: VecDeque<f64>,
+    load: Option<f64>,
+    memory: Option<f64>,
+}
+
+impl SystemSegment {
+    pub fn new(config: &Config) -> Self {
+        SystemSegment {
+            proc_path: PathBuf::from(&config.system_proc_path),
+            gauge: config.system_gauge,
+            refresh_interval: config.system_refresh_interval,
+            warning_threshold: config.system_warning_threshold,
+            critical_threshold: config.system_critical_threshold,
+            elapsed: 0.0,
+            available: true,
+            cpu_count: 1,
+            cpu_times: None,
+            cpu_history: VecDeque::with_capacity(SPARKLINE_LENGTH),
+            load: None,
+            memory: None,
+        }
+    }
+
+    fn read(&self, file: &str) -> Option<String> {
+        fs::read_to_string(self.proc_path.join(file)).ok()
+    }
+
+    pub fn refresh(&mut self) {
+        self.elapsed = 0.0;
+
+        self.load = self.read("loadavg").as_deref().and_then(parse_load);
+        self.memory = self.read("meminfo").as_deref().and_then(parse_memory_usage);
+
+        let stat = self.read("stat");
+        let cpu_times = stat.as_deref().and_then(CpuTimes::parse);
+        let available = self.load.is_some() || self.memory.is_some() || cpu_times.is_some();
+        if self.available && !available {
+            eprintln!("oneliner: can't read {}, is `system_proc_path` reachable from the plugin?", self.proc_path.display());
+        }
+        self.available = available;
+
+        let Some(stat) = stat.filter(|_| cpu_times.is_some()) else {
+            self.cpu_times = None;
+            self.cpu_history.clear();
+            return;
+        };
+        self.cpu_count = parse_cpu_count(&stat);
+
+        // CPU usage is only known between two samples
+        if let Some(usage) = cpu_times.zip(self.cpu_times).and_then(|(current, previous)| current.usage_since(&previous)) {
+            if self.cpu_history.len() == SPARKLINE_LENGTH {
+                self.cpu_history.pop_front();
+            }
+            self.cpu_history.push_back(usage);
+        }
+        self.cpu_times = cpu_times;
+    }
+
+    pub fn tick(&mut self, secs: f64) -> bool {
+        self.elapsed += secs;
+        if self.elapsed < self.refresh_interval { return false; }
+
+        self.refresh();
+        true
+    }
+
+    fn level(&self, percentage: f64) -> SegmentLevel {
+        if percentage >= self.critical_threshold {
+            SegmentLevel::Critical
+        } else if percentage >= self.warning_threshold {
+            SegmentLevel::Warning
+        } else {
+            SegmentLevel::Normal
+        }
+    }
+
+    fn sparkline(percentages: impl Iterator<Item = f64>, fonts: FontProfile) -> String {
+        let blocks = if fonts == FontProfile::Ascii { &SPARKLINE_ASCII } else { &SPARKLINE_BLOCKS };
+
+        percentages
+            .map(|percentage| {
+                let index = (percentage.clamp(0.0, 100.0) / 100.0 * (blocks.len() - 1) as f64).round() as usize;
+                blocks[index]
+            })
+            .collect()
+    }
+
+    pub fn status_line(&self, colored_elements: &ColoredElements) -> StatusLine {
+        if !self.available {
+            return segment_status(&[
+                colored_elements.segment.paint(" sys "),
+                colored_elements.segment_warning.paint(UNAVAILABLE_MARKER),
+                colored_elements.segment.paint(" "),
+            ]);
+        }
+
+        let mut bits = vec![];
+        let mut gauge = |label: &str, value: String, percentage: f64| {
+            bits.push(colored_elements.segment.paint(format!(" {label} ")));
+            bits.push(colored_elements.segment_style(self.level(percentage)).paint(value));
+        };
+
+        if let Some(load) = self.load {
+            // Load is relative to the number of CPUs available
+            gauge("load", format!("{load:.2}"), 100.0 * load / self.cpu_count as f64);
+        }
+        if let Some(&cpu) = self.cpu_history.back() {
+            let value = match self.gauge {
+                Gauge::Numbers => format!("{cpu:.0}%"),
+                Gauge::Sparkline => Self::sparkline(self.cpu_history.iter().copied(), colored_elements.fonts),
+            };
+            gauge("cpu", value, cpu);
+        }
+        if let Some(memory) = self.memory {
+            let value = match self.gauge {
+                Gauge::Numbers => format!("{memory:.0}%"),
+                Gauge::Sparkline => Self::sparkline(std::iter::once(memory), colored_elements.fonts),
+            };
+            gauge("mem", value, memory);
+        }
+
+        if bits.is_empty() { return StatusLine::default(); }
+        bits.push(colored_elements.segment.paint(" "));
+
+        segment_status(&bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{parse_cpu_count, parse_load, parse_memory_usage, CpuTimes, SystemSegment};
+    use crate::modules::colored_elements::ColoredElements;
+    use crate::modules::config::Config;
+    use crate::modules::fonts::FontProfile;
+
+    static STAT: &str = "cpu  4705 356 584 3699 23 23 0 0 0 0
+cpu0 1393280 32966 572056 13343292 6130 0 17875 0 23933 0
+cpu1 1335463 22458 577380 13403478 5936 0 7010 0 21845 0
+intr 114930548 113199788 3 0 5 263 0 4 [... lots more numbers ...]
+ctxt 1990473
+btime 1062191376
+";
+    static MEMINFO: &str = "MemTotal:       16304436 kB
+MemFree:         1211532 kB
+MemAvailable:    4076109 kB
+Buffers:          321540 kB
+";
+
+    #[test]
+    fn parses_load() {
+        assert_eq!(parse_load("0.72 1.06 1.20 2/1310 98765\n"), Some(0.72));
+        assert_eq!(parse_load(""), None);
+        assert_eq!(parse_load("high 1.06 1.20"), None);
+    }
+
+    #[test]
+    fn parses_memory_usage() {
+        let usage = parse_memory_usage(MEMINFO).unwrap();
+        assert!((usage - 75.0).abs() < 0.01, "{usage}");
+
+        assert_eq!(parse_memory_usage(""), None);
+        assert_eq!(parse_memory_usage("MemTotal:       16304436 kB\n"), None);
+        assert_eq!(parse_memory_usage("MemTotal: 0 kB\nMemAvailable: 0 kB\n"), None);
+        assert_eq!(parse_memory_usage("MemTotal: lots\nMemAvailable: 12 kB\n"), None);
+        // More available than total, e.g. read across an update
+        assert_eq!(parse_memory_usage("MemTotal: 10 kB\nMemAvailable: 12 kB\n"), Some(0.0));
+    }
+
+    #[test]
+    fn parses_cpu_times() {
+        let times = CpuTimes::parse(STAT).unwrap();
+        assert_eq!((times.idle, times.total), (3699 + 23, 4705 + 356 + 584 + 3699 + 23 + 23));
+        assert_eq!(parse_cpu_count(STAT), 2);
+
+        assert!(CpuTimes::parse("").is_none());
+        assert!(CpuTimes::parse("cpu  4705 356 584\n").is_none());
+        assert!(CpuTimes::parse("cpu0 1393280 32966 572056 13343292 6130\n").is_none());
+        assert!(CpuTimes::parse("intr 114930548\n").is_none());
+        assert_eq!(parse_cpu_count("cpu  4705 356 584 3699 23\n"), 1);
+    }
+
+    #[test]
+    fn measures_cpu_usage_between_samples() {
+        let previous = CpuTimes { idle: 100, total: 200 };
+
+        assert_eq!(CpuTimes { idle: 130, total: 240 }.usage_since(&previous), Some(25.0));
+        assert_eq!(CpuTimes { idle: 100, total: 200 }.usage_since(&previous), None);
+        // Counters going backwards, e.g. on CPU hotplug
+        assert_eq!(CpuTimes { idle: 90, total: 150 }.usage_since(&previous), None);
+        assert_eq!(CpuTimes { idle: 90, total: 210 }.usage_since(&previous), Some(100.0));
+    }
+
+    #[test]
+    fn shows_unavailable_marker_for_unreadable_or_malformed_procfs() {
+        let proc_path = std::env::temp_dir().join(format!("oneliner-proc-{}", std::process::id()));
+        fs::create_dir_all(&proc_path).unwrap();
+        fs::write(proc_path.join("loadavg"), "garbage").unwrap();
+        fs::write(proc_path.join("meminfo"), "MemTotal:").unwrap();
+        fs::write(proc_path.join("stat"), "cpu 1 2").unwrap();
+
+        let config = Config {
+            system_proc_path: proc_path.display().to_string(),
+            ..Config::default()
+        };
+        let colored_elements = ColoredElements::color_elements(&Default::default(), FontProfile::Ascii);
+        let mut segment = SystemSegment::new(&config);
+        segment.refresh();
+        assert_eq!(segment.status_line(&colored_elements).to_plain(), " sys n/a ");
+
+        fs::write(proc_path.join("loadavg"), "0.50 0.40 0.30 1/100 1234\n").unwrap();
+        fs::write(proc_path.join("stat"), STAT).unwrap();
+        segment.refresh();
+        assert_eq!(segment.status_line(&colored_elements).to_plain(), " load 0.50 ");
+
+        fs::remove_dir_all(&proc_path).unwrap();
+        segment.refresh();
+        assert_eq!(segment.status_line(&colored_elements).to_plain(), " sys n/a ");
+    }
+}
@@ -0,0 +1,238 @@
+use std::fmt;
+
+use ansi_term::{ANSIString, Colour, Style};
+use strum::Display;
+use zellij_tile::prelude::actions::Action;
+
+// Clears the rest of the line with the current background
+static CLEAR_TO_END_OF_LINE: &str = "\u{1b}[0K";
+
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum Role {
+    Tile,
+    Key,
+    Hint,
+    Separator,
+    Filler,
+    Segment,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub role: Role,
+    pub style: Style,
+    // Action to carry out when the span is clicked, if any
+    pub target: Option<Action>,
+}
+
+impl Span {
+    pub fn new(text: impl Into<String>, role: Role, style: Style) -> Self {
+        Span {
+            text: text.into(),
+            role,
+            style,
+            target: None,
+        }
+    }
+
+    pub fn with_target(mut self, target: Action) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Filler without text stretches to the end of the line
+    fn is_fill(&self) -> bool {
+        self.role == Role::Filler && self.is_empty()
+    }
+}
+
+impl From<&ANSIString<'static>> for Span {
+    fn from(string: &ANSIString<'static>) -> Self {
+        Span::new(&**string, Role::Segment, *string.style_ref())
+    }
+}
+
+/// Writes `spans` as ANSI escape sequences into `out`, only emitting the changes in style between spans
+pub fn write_ansi(spans: &[Span], out: &mut impl fmt::Write) -> fmt::Result {
+    let mut previous: Option<Style> = None;
+
+    for span in spans {
+        match previous {
+            Some(previous) => write!(out, "{}", previous.infix(span.style))?,
+            None => write!(out, "{}", span.style.prefix())?,
+        }
+        out.write_str(if span.is_fill() { CLEAR_TO_END_OF_LINE } else { &span.text })?;
+        previous = Some(span.style);
+    }
+
+    match previous {
+        Some(last) => write!(out, "{}", last.suffix()),
+        None => Ok(()),
+    }
+}
+
+/// Serialises `spans` into ANSI escape sequences, for the terminal
+pub fn to_ansi(spans: &[Span]) -> String {
+    let mut ansi = String::with_capacity(spans.iter().map(|span| span.text.len()).sum());
+    push_ansi(spans, &mut ansi);
+    ansi
+}
+
+/// Appends the ANSI serialisation of `spans` to `out`, reusing its allocation
+pub fn push_ansi(spans: &[Span], out: &mut String) {
+    // Writing into a `String` never fails
+    let _ = write_ansi(spans, out);
+}
+
+/// Serialises `spans` into plain text, without any styling
+pub fn to_plain(spans: &[Span]) -> String {
+    spans.iter().map(|span| &span.text[..]).collect()
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+
+    escaped
+}
+
+fn json_colour(colour: Option<Colour>) -> String {
+    match colour {
+        Some(Colour::RGB(r, g, b)) => json_string(&format!("#{r:02x}{g:02x}{b:02x}")),
+        Some(Colour::Fixed(n)) => n.to_string(),
+        Some(colour) => json_string(&format!("{colour:?}").to_lowercase()),
+        None => "null".to_string(),
+    }
+}
+
+fn json_style(style: &Style) -> String {
+    format!(
+        "{{\"fg\":{},\"bg\":{},\"bold\":{},\"dimmed\":{},\"italic\":{},\"underline\":{},\"strikethrough\":{}}}",
+        json_colour(style.foreground),
+        json_colour(style.background),
+        style.is_bold,
+        style.is_dimmed,
+        style.is_italic,
+        style.is_underline,
+        style.is_strikethrough,
+    )
+}
+
+/// Serialises `spans` into a JSON array, for external tooling
+pub fn to_json(spans: &[Span]) -> String {
+    let spans = spans
+        .iter()
+        .map(|span| {
+            let target = match &span.target {
+                Some(target) => json_string(&format!("{target:?}")),
+                None => "null".to_string(),
+            };
+
+            format!(
+                "{{\"text\":{},\"role\":{},\"style\":{},\"target\":{}}}",
+                json_string(&span.text),
+                json_string(&span.role.to_string()),
+                json_style(&span.style),
+                target,
+            )
+        })
+        .collect::<Vec<String>>();
+
+    format!("[{}]", spans.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use ansi_term::{Colour, Style};
+    use zellij_tile::prelude::actions::Action;
+    use zellij_tile::prelude::*;
+
+    use super::{to_ansi, to_json, to_plain, Role, Span};
+
+    // A superkey, a tile switching to Pane mode, a hint and the filler
+    fn spans(restyle: impl Fn(Style) -> Style) -> Vec<Span> {
+        vec![
+            Span::new(" Ctrl +", Role::Key, restyle(Style::new().fg(Colour::RGB(0xcd, 0xd6, 0xf4)).bold())),
+            Span::new(" <p> PANE ", Role::Tile, restyle(Colour::Black.on(Colour::Green)))
+                .with_target(Action::SwitchToMode(InputMode::Pane)),
+            Span::new(" \"quoted\"", Role::Hint, restyle(Style::new().fg(Colour::Fixed(245)).italic())),
+            Span::new("", Role::Filler, restyle(Style::new().on(Colour::Fixed(236)))),
+        ]
+    }
+
+    fn monochrome(style: Style) -> Style {
+        Style {
+            foreground: None,
+            background: None,
+            ..style
+        }
+    }
+
+    #[test]
+    fn writes_plain_text() {
+        let plain = " Ctrl + <p> PANE  \"quoted\"";
+
+        assert_eq!(to_plain(&spans(|style| style)), plain);
+        assert_eq!(to_plain(&spans(monochrome)), plain);
+        assert_eq!(to_plain(&spans(|_| Style::new())), plain);
+    }
+
+    #[test]
+    fn writes_ansi_with_style_changes_only() {
+        assert_eq!(
+            to_ansi(&spans(|style| style)),
+            "\u{1b}[1;38;2;205;214;244m Ctrl +\u{1b}[0m\u{1b}[42;30m <p> PANE \u{1b}[0m\u{1b}[3;38;5;245m \"quoted\"\u{1b}[0m\
+             \u{1b}[48;5;236m\u{1b}[0K\u{1b}[0m",
+        );
+        assert_eq!(to_ansi(&[]), "");
+    }
+
+    #[test]
+    fn writes_monochrome_ansi_as_attributes_only() {
+        assert_eq!(to_ansi(&spans(monochrome)), "\u{1b}[1m Ctrl +\u{1b}[0m <p> PANE \u{1b}[3m \"quoted\"\u{1b}[0m\u{1b}[0K");
+        // Without any styling, only the filler's clearing is left
+        assert_eq!(to_ansi(&spans(|_| Style::new())), " Ctrl + <p> PANE  \"quoted\"\u{1b}[0K");
+    }
+
+    #[test]
+    fn writes_json() {
+        let style = |fg: &str, bg: &str, bold: bool, italic: bool| {
+            format!(
+                "{{\"fg\":{fg},\"bg\":{bg},\"bold\":{bold},\"dimmed\":false,\"italic\":{italic},\"underline\":false,\
+                 \"strikethrough\":false}}"
+            )
+        };
+        let expected = [
+            format!("{{\"text\":\" Ctrl +\",\"role\":\"key\",\"style\":{},\"target\":null}}", style("\"#cdd6f4\"", "null", true, false)),
+            format!(
+                "{{\"text\":\" <p> PANE \",\"role\":\"tile\",\"style\":{},\"target\":\"SwitchToMode(Pane)\"}}",
+                style("\"black\"", "\"green\"", false, false)
+            ),
+            format!("{{\"text\":\" \\\"quoted\\\"\",\"role\":\"hint\",\"style\":{},\"target\":null}}", style("245", "null", false, true)),
+            format!("{{\"text\":\"\",\"role\":\"filler\",\"style\":{},\"target\":null}}", style("null", "236", false, false)),
+        ];
+
+        assert_eq!(to_json(&spans(|style| style)), format!("[{}]", expected.join(",")));
+        assert_eq!(to_json(&[]), "[]");
+    }
+}
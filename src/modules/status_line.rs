@@ -1,94 +1,126 @@
 use std::fmt::{Display, Error, Formatter};
 
-use ansi_term::ANSIStrings;
-use zellij_tile::prelude::actions::Action;
 use zellij_tile::prelude::*;
 
-use super::colored_elements::ColoredElements;
+use super::colored_elements::{ColorProfile, ColoredElements, SegmentLevel};
+use super::config::Config;
+use super::keybind_index::{KeybindIndex, ModeKeybinds};
 use super::key_shortcut::{self, KeyShortcut};
+use super::notification::Notifications;
+use super::segments::Segments;
+use super::span::{self, Role, Span};
+use super::tips::Tip;
 use super::utils;
 
 static MORE_MSG: &str = " ... ";
+static TIP_MSG: &str = "  Tip:";
 static MINIMAL_LENGTH_FOR_FULL_SHORTCUT_PRINTING: usize = 136;
 
 #[derive(Default)]
 pub struct StatusLine {
-    pub part: String,
-    pub len: usize,
+    spans: Vec<Span>,
+    // Width of the spans, kept up to date as spans are added rather than recounted
+    len: usize,
+    // Whether the tip passed to the builder fit in the line
+    shows_tip: bool,
 }
 
 impl Display for StatusLine {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-        write!(f, "{}", self.part)
+        span::write_ansi(&self.spans, f)
     }
 }
 
 impl StatusLine {
-    fn superkey(
-        keybinds: &[(Key, Vec<Action>)], colored_elements: &ColoredElements, separator: &str, simplified_ui: bool,
-    ) -> StatusLine {
-        let mut superkeys = keybinds
-            .iter()
-            // Keep only `SwitchToMode` and `Quit` key-action entries and map to its superkey
-            .filter_map(utils::filter_get_superkey);
-
-        match superkeys.next() {
-            // Check if all superkeys are the same, if keys exist
-            Some(superkey) if superkeys.all(|str| str == superkey) => {
-                let prefix_text = format!(" {superkey} +{}", if simplified_ui { " " } else { "" });
-
-                let prefix = colored_elements.superkey_prefix.paint(&prefix_text);
-                let suffix_separator = colored_elements.superkey_suffix_separator.paint(separator);
-
-                StatusLine {
-                    part: ANSIStrings(&[prefix, suffix_separator]).to_string(),
-                    len: prefix_text.chars().count() // Superkey
-                        + separator.chars().count(), // Separator
-                }
+    pub fn new(spans: Vec<Span>) -> Self {
+        let len = spans.iter().map(Span::len).sum();
+        StatusLine { spans, len, shows_tip: false }
+    }
+
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Whether the tip passed to the builder is shown, as it is left out when there is no room for it
+    pub fn shows_tip(&self) -> bool {
+        self.shows_tip
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn push(&mut self, span: Span) {
+        self.len += span.len();
+        self.spans.push(span);
+    }
+
+    fn extend(&mut self, spans: impl IntoIterator<Item = Span>) {
+        spans.into_iter().for_each(|span| self.push(span));
+    }
+
+    fn append(&mut self, other: StatusLine) {
+        self.len += other.len;
+        self.spans.extend(other.spans);
+    }
+
+    fn superkey(keybinds: &ModeKeybinds, colored_elements: &ColoredElements) -> StatusLine {
+        let separator = colored_elements.fonts.separator();
+
+        match keybinds.superkey() {
+            // All `SwitchToMode` and `Quit` keys share the same superkey
+            Some(superkey) => {
+                let padding = if separator.is_empty() { " " } else { "" };
+                let prefix_text = format!(" {}{padding}", colored_elements.notation.superkey(superkey));
+
+                StatusLine::new(vec![
+                    Span::new(prefix_text, Role::Tile, colored_elements.superkey_prefix),
+                    Span::new(separator, Role::Separator, colored_elements.superkey_suffix_separator),
+                ])
             }
             // Otherwise, don't print superkey
             _ => StatusLine::default(),
         }
     }
 
-    fn shortcuts(&mut self, shortcuts: Vec<KeyShortcut>, colored_elements: &ColoredElements, separator: &str, max_len: usize) {
-        let shared_super = self.len > 0;
-        let mut line_empty = self.len == 0;
+    fn shortcuts(&mut self, shortcuts: Vec<KeyShortcut>, colored_elements: &ColoredElements, max_len: usize) {
+        let shared_super = !self.is_empty();
+        let mut line_empty = self.is_empty();
 
         for shortcut in shortcuts {
             // Build up StatusLine one shortcut at a time
             let shortcut_status = shortcut.generate_status(
                 colored_elements,
-                separator,
                 max_len > MINIMAL_LENGTH_FOR_FULL_SHORTCUT_PRINTING,
                 shared_super,
                 line_empty,
             );
 
             // Append to self
-            self.part = format!("{}{}", self.part, shortcut_status.part);
-            self.len += shortcut_status.len;
-            line_empty = line_empty && self.len == 0;
+            self.append(shortcut_status);
+            line_empty = line_empty && self.is_empty();
         }
     }
 
     fn add_shortcut_keybindings(&mut self, colored_elements: &ColoredElements, text: &str, keys: &[Key], is_locked_mode: bool) {
         if keys.is_empty() && !is_locked_mode { return; }
 
-        let separator = if self.len == 0 { " " } else { " / " };
-        let mut bits = vec![colored_elements.text.paint(separator)];
-        bits.extend(colored_elements.paint_keys(keys));
-        bits.push(colored_elements.text.bold().paint(format!(" {text}")));
-        let part = ANSIStrings(&bits);
-
-        self.part = format!("{}{}", self.part, part);
-        self.len += ansi_term::unstyled_len(&part);
+        let separator = if self.is_empty() { " " } else { " / " };
+        self.push(Span::new(separator, Role::Separator, colored_elements.text));
+        self.extend(colored_elements.paint_keys(keys));
+        self.push(Span::new(format!(" {text}"), Role::Hint, colored_elements.text.bold()));
     }
 
-    fn nonstandard_mode_hints(&mut self, mode_info: &ModeInfo, colored_elements: &ColoredElements, max_len: usize) {
-        let keys_and_hints = utils::get_keys_and_hints(mode_info);
-        let more_msg = colored_elements.text.paint(MORE_MSG);
-        let is_locked_mode = mode_info.mode == InputMode::Locked;
+    fn nonstandard_mode_hints(
+        &mut self, mode: InputMode, keybinds: &ModeKeybinds, colored_elements: &ColoredElements, max_len: usize,
+    ) {
+        let keys_and_hints = utils::get_keys_and_hints(mode, keybinds);
+        let more_msg = Span::new(MORE_MSG, Role::Hint, colored_elements.text);
+        let is_locked_mode = mode == InputMode::Locked;
 
         let mut full_hints = StatusLine::default();
         let mut short_hints = StatusLine::default();
@@ -97,13 +129,13 @@ impl StatusLine {
             if !is_full_overflowing {
                 // Build the full version as long as it fits
                 full_hints.add_shortcut_keybindings(colored_elements, &long, &keys, is_locked_mode);
-                is_full_overflowing = self.len + full_hints.len > max_len;
+                is_full_overflowing = self.len() + full_hints.len() > max_len;
             }
 
-            if self.len + short_hints.len + 6 + keys.len() + short.chars().count() + MORE_MSG.chars().count() > max_len {
+            if self.len() + short_hints.len() + 6 + keys.len() + short.chars().count() + MORE_MSG.chars().count() > max_len {
                 // StatusLine is long enough, finishing
-                self.part = format!("{}{}{}", self.part, short_hints.part, more_msg);
-                self.len += short_hints.len + MORE_MSG.chars().count();
+                self.append(short_hints);
+                self.push(more_msg);
                 return;
             }
             // Build the short version of StatusLine
@@ -112,31 +144,409 @@ impl StatusLine {
 
         // Return the full version if possible, otherwise return the short version
         let actual_hints = if is_full_overflowing { short_hints } else { full_hints };
-        self.part = format!("{}{}", self.part, actual_hints.part);
-        self.len += actual_hints.len;
+        self.append(actual_hints);
+    }
+
+    fn notification(&mut self, notifications: &Notifications, colored_elements: &ColoredElements, max_len: usize) {
+        let Some(notification) = notifications.current() else { return };
+
+        let queued = match notifications.queued() {
+            0 => String::new(),
+            count => format!(" (+{count})"),
+        };
+
+        // Shorten the message to what fits
+        let available = max_len.saturating_sub(self.len() + 1 + queued.chars().count());
+        let message = if notification.message.chars().count() > available {
+            let mut message = notification.message.chars().take(available.saturating_sub(1)).collect::<String>();
+            message.push_str(colored_elements.fonts.ellipsis());
+            message
+        } else {
+            notification.message.clone()
+        };
+        if available == 0 { return; }
+
+        self.extend([
+            Span::new(" ", Role::Separator, colored_elements.text),
+            Span::new(message, Role::Hint, notification.style(colored_elements)),
+            Span::new(queued, Role::Hint, colored_elements.text),
+        ]);
+    }
+
+    fn tip(&mut self, tip: &Tip, colored_elements: &ColoredElements, max_len: usize) {
+        let mut tip_status = StatusLine::new(vec![Span::new(TIP_MSG, Role::Hint, colored_elements.text)]);
+        for key in &tip.keys {
+            tip_status.push(Span::new(" ", Role::Separator, colored_elements.text));
+            tip_status.extend(colored_elements.paint_keys(&[*key]));
+        }
+        tip_status.push(Span::new(format!(" {}", tip.text), Role::Hint, colored_elements.text.bold()));
+
+        // Only show the tip in otherwise empty space
+        if self.len() + tip_status.len() > max_len { return; }
+
+        self.append(tip_status);
+        self.shows_tip = true;
+    }
+
+    fn fitting_segments(&self, segments: Vec<StatusLine>, max_len: usize) -> Vec<StatusLine> {
+        let mut len = self.len();
+
+        // Keep segments in order of priority, as long as they fit
+        segments
+            .into_iter()
+            .filter(|segment| !segment.is_empty())
+            .take_while(|segment| {
+                len += segment.len();
+                len <= max_len
+            })
+            .collect()
+    }
+
+    fn right_align(&mut self, segments: Vec<StatusLine>, colored_elements: &ColoredElements, max_len: usize) {
+        if segments.is_empty() { return; }
+
+        let segments_len = segments.iter().map(StatusLine::len).sum::<usize>();
+        let padding = max_len.saturating_sub(self.len() + segments_len);
+        self.push(Span::new(" ".repeat(padding), Role::Filler, colored_elements.filler));
+
+        for segment in segments {
+            self.append(segment);
+        }
     }
 
     fn fill(&mut self, colored_elements: &ColoredElements) {
-        self.part = format!("{}{}", self.part, colored_elements.filler.paint("\u{1b}[0K"));
+        // Filler without text stretches to the end of the line
+        self.push(Span::new("", Role::Filler, colored_elements.filler));
+    }
+
+    pub fn to_ansi(&self) -> String {
+        span::to_ansi(&self.spans)
+    }
+
+    pub fn to_plain(&self) -> String {
+        span::to_plain(&self.spans)
+    }
+
+    pub fn to_json(&self) -> String {
+        span::to_json(&self.spans)
+    }
+
+    /// Starts building the status line for `mode_info`
+    pub fn builder(mode_info: &ModeInfo) -> StatusLineBuilder<'_> {
+        StatusLineBuilder {
+            mode_info,
+            keybind_index: None,
+            config: None,
+            width: 0,
+            segments: None,
+            notifications: None,
+            tip: None,
+            escalation: None,
+            focused_pane: None,
+            tabs: None,
+            original_name: None,
+        }
+    }
+}
+
+/// Builds a `StatusLine` from Zellij's state, see `StatusLine::builder`
+pub struct StatusLineBuilder<'a> {
+    mode_info: &'a ModeInfo,
+    keybind_index: Option<&'a KeybindIndex>,
+    config: Option<&'a Config>,
+    width: usize,
+    segments: Option<&'a Segments>,
+    notifications: Option<&'a Notifications>,
+    tip: Option<Tip>,
+    escalation: Option<SegmentLevel>,
+    focused_pane: Option<&'a PaneInfo>,
+    tabs: Option<&'a [TabInfo]>,
+    original_name: Option<&'a str>,
+}
+
+impl<'a> StatusLineBuilder<'a> {
+    /// Keybinds indexed from `mode_info`, otherwise indexed while building
+    pub fn keybind_index(mut self, keybind_index: &'a KeybindIndex) -> Self {
+        self.keybind_index = Some(keybind_index);
+        self
+    }
+
+    /// Configuration to render with, otherwise the defaults
+    pub fn config(mut self, config: &'a Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Number of columns available
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Segments shown on the right, in order of priority
+    pub fn segments(mut self, segments: &'a Segments) -> Self {
+        self.segments = Some(segments);
+        self
+    }
+
+    /// Notifications shown in place of the hints while active
+    pub fn notifications(mut self, notifications: &'a Notifications) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Tip shown in the space left over by the hints
+    pub fn tip(mut self, tip: Option<Tip>) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    /// Highlights the hints with the style of `level`
+    pub fn escalation(mut self, level: Option<SegmentLevel>) -> Self {
+        self.escalation = level;
+        self
     }
 
-    pub fn build(
-        mode_info: &ModeInfo, keybinds: &[(Key, Vec<Action>)], colored_elements: &ColoredElements, simplified_ui: bool,
-        separator: &str, max_len: usize,
-    ) -> StatusLine {
+    /// Pane focused in the active tab, whose size and position are shown while resizing and moving it
+    pub fn focused_pane(mut self, pane: Option<&'a PaneInfo>) -> Self {
+        self.focused_pane = pane;
+        self
+    }
+
+    /// Tabs, whose active one's name is shown while renaming it
+    pub fn tabs(mut self, tabs: &'a [TabInfo]) -> Self {
+        self.tabs = Some(tabs);
+        self
+    }
+
+    /// Name of the tab or pane being renamed, before renaming it
+    pub fn original_name(mut self, name: Option<&'a str>) -> Self {
+        self.original_name = name;
+        self
+    }
+
+    /// Context of the current mode: the size of the focused pane in Resize mode, its position in Move mode, and the
+    /// name being typed in the Rename modes, after the original name unless `with_original` is unset
+    fn mode_context(&self, colored_elements: &ColoredElements, with_original: bool) -> StatusLine {
+        let fonts = colored_elements.fonts;
+        let mut spans = vec![Span::new("  ", Role::Separator, colored_elements.text)];
+
+        match (self.mode_info.mode, self.focused_pane) {
+            (InputMode::Resize, Some(pane)) => {
+                let size = format!("{}{}{}", pane.pane_columns, fonts.glyph("×", "x"), pane.pane_rows);
+                spans.push(Span::new(size, Role::Hint, colored_elements.segment_accent));
+            }
+            (InputMode::Move, Some(pane)) => {
+                let position = format!("@ {},{}", pane.pane_x, pane.pane_y);
+                spans.push(Span::new(position, Role::Hint, colored_elements.segment_accent));
+            }
+            (mode @ (InputMode::RenameTab | InputMode::RenamePane), pane) => {
+                let name = match mode {
+                    InputMode::RenameTab => self.tabs.and_then(|tabs| tabs.iter().find(|tab| tab.active)).map(|tab| &tab.name),
+                    _ => pane.map(|pane| &pane.title),
+                };
+                let Some(name) = name else { return StatusLine::default() };
+
+                if let Some(original) = self.original_name.filter(|original| with_original && *original != name) {
+                    spans.extend([
+                        Span::new(original, Role::Hint, colored_elements.text.dimmed().strikethrough()),
+                        Span::new(fonts.glyph(" → ", " -> "), Role::Separator, colored_elements.text),
+                    ]);
+                }
+                spans.push(Span::new(name.clone(), Role::Hint, colored_elements.segment_accent));
+            }
+            _ => return StatusLine::default(),
+        }
+
+        StatusLine::new(spans)
+    }
+
+    pub fn build(self) -> StatusLine {
+        let default_config = Config::default();
+        let config = self.config.unwrap_or(&default_config);
+
+        let fonts = config.fonts.resolve(&self.mode_info.capabilities);
+        let palette = &self.mode_info.style.colors;
+        let mut colored_elements = config.theme.colored_elements(palette, fonts);
+        if config.mode_accents {
+            let accent = config.accents.accent(self.mode_info.mode).colour(config.theme, palette);
+            colored_elements = colored_elements.with_accent(accent, config.mode_accent_filler);
+        }
+        // Contrast is ensured between the colors the terminal shows, not the ones the theme asks for
+        let depth = config.color_depth;
+        colored_elements = colored_elements
+            .with_color_depth(depth)
+            .with_minimum_contrast(config.theme.minimum_contrast(config.minimum_contrast), depth)
+            .with_notation(config.key_notation);
+        if config.color_blind_safe {
+            colored_elements = colored_elements.with_shape_cues();
+        }
+        colored_elements = colored_elements.with_color_profile(config.colors);
+        if let Some(level) = self.escalation {
+            colored_elements = colored_elements.escalated(level);
+        }
+
+        let segments = match self.segments {
+            Some(segments) => segments.status_lines(&colored_elements),
+            None => vec![],
+        };
+        let (mode_info, max_len) = (self.mode_info, self.width);
+        let built_index;
+        let keybind_index = match self.keybind_index {
+            Some(keybind_index) => keybind_index,
+            None => {
+                built_index = KeybindIndex::new(mode_info);
+                &built_index
+            }
+        };
+        let keybinds = keybind_index.mode(mode_info.mode);
+
         // Initial StatusLine with superkey indicator
-        let mut status = Self::superkey(keybinds, colored_elements, separator, simplified_ui);
+        let mut status = StatusLine::superkey(keybinds, &colored_elements);
 
         // Append shortcuts to status
         let shortcuts = key_shortcut::generate_shortcuts(keybinds, &mode_info.mode);
-        status.shortcuts(shortcuts, colored_elements, separator, max_len);
+        status.shortcuts(shortcuts, &colored_elements, max_len);
+
+        // Reserve space for the segments which fit on the right
+        let segments = status.fitting_segments(segments, max_len);
+        let segments_len = segments.iter().map(StatusLine::len).sum::<usize>();
+
+        if let Some(notifications) = self.notifications.filter(|notifications| notifications.is_active()) {
+            // Notifications temporarily take the place of the hints
+            status.notification(notifications, &colored_elements, max_len - segments_len);
+        } else {
+            // Append key bindings and hints for each nonstandard modes, keeping room for the mode's context
+            let available = max_len - segments_len;
+            let mut mode_context = self.mode_context(&colored_elements, true);
+            if status.len() + mode_context.len() > available {
+                mode_context = self.mode_context(&colored_elements, false);
+            }
+            status.nonstandard_mode_hints(mode_info.mode, keybinds, &colored_elements, available.saturating_sub(mode_context.len()));
+            if status.len() + mode_context.len() <= available {
+                status.append(mode_context);
+            }
+
+            // Append a tip in the spare space, if any
+            if let Some(tip) = self.tip {
+                status.tip(&tip, &colored_elements, max_len - segments_len);
+            }
+        }
 
-        // Append key bindings and hints for each nonstandard modes
-        status.nonstandard_mode_hints(mode_info, colored_elements, max_len);
+        // Append right-aligned segments
+        status.right_align(segments, &colored_elements, max_len);
 
         // Fill the rest of the line
-        status.fill(colored_elements);
+        status.fill(&colored_elements);
+
+        // Some spans derive their style from the elements, e.g. bold hints
+        if config.colors == ColorProfile::Plain {
+            status.spans.iter_mut().for_each(|span| span.style = ansi_term::Style::new());
+        }
 
         status
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zellij_tile::prelude::actions::Action;
+    use zellij_tile::prelude::*;
+
+    use super::StatusLine;
+    use crate::modules::colored_elements::ColorProfile;
+    use crate::modules::config::Config;
+
+    fn resize_mode() -> ModeInfo {
+        let keybinds = vec![
+            (Key::Char('+'), vec![Action::Resize(Resize::Increase, None)]),
+            (Key::Char('-'), vec![Action::Resize(Resize::Decrease, None)]),
+        ];
+
+        ModeInfo {
+            mode: InputMode::Resize,
+            keybinds: vec![(InputMode::Resize, keybinds)],
+            ..ModeInfo::default()
+        }
+    }
+
+    fn pane_mode() -> ModeInfo {
+        let keybinds = vec![
+            (Key::Ctrl('p'), vec![Action::SwitchToMode(InputMode::Normal)]),
+            (Key::Char('x'), vec![Action::CloseFocus]),
+        ];
+
+        ModeInfo {
+            mode: InputMode::Pane,
+            keybinds: vec![(InputMode::Pane, keybinds)],
+            ..ModeInfo::default()
+        }
+    }
+
+    fn build_with(mode_info: &ModeInfo, colors: ColorProfile, color_blind_safe: bool) -> StatusLine {
+        let config = Config {
+            colors,
+            color_blind_safe,
+            ..Config::default()
+        };
+        StatusLine::builder(mode_info).config(&config).width(200).build()
+    }
+
+    fn build(mode_info: &ModeInfo, pane: Option<&PaneInfo>, width: usize) -> StatusLine {
+        StatusLine::builder(mode_info).width(width).focused_pane(pane).build()
+    }
+
+    #[test]
+    fn appends_mode_context_after_hints() {
+        let (mode_info, pane) = (resize_mode(), PaneInfo { pane_columns: 80, pane_rows: 24, ..PaneInfo::default() });
+
+        assert_eq!(build(&mode_info, Some(&pane), 50).to_plain(), " <+|-> Increase/Decrease size  80×24");
+    }
+
+    #[test]
+    fn shortens_hints_to_fit_mode_context() {
+        let (mode_info, pane) = (resize_mode(), PaneInfo { pane_columns: 80, pane_rows: 24, ..PaneInfo::default() });
+
+        // The full hints would fit on their own, the short ones are used to keep the context
+        assert_eq!(build(&mode_info, None, 45).to_plain(), " <+|-> Increase/Decrease size");
+        let status = build(&mode_info, Some(&pane), 45);
+        assert_eq!(status.to_plain(), " <+|-> Increase/Decrease ...   80×24");
+        assert!(status.len() <= 45);
+    }
+
+    #[test]
+    fn drops_mode_context_when_it_does_not_fit() {
+        let (mode_info, pane) = (resize_mode(), PaneInfo { pane_columns: 80, pane_rows: 24, ..PaneInfo::default() });
+
+        assert_eq!(build(&mode_info, Some(&pane), 10).to_plain(), build(&mode_info, None, 10).to_plain());
+    }
+
+    #[test]
+    fn shows_shape_cues_in_every_color_profile() {
+        let mode_info = pane_mode();
+
+        for colors in [ColorProfile::Color, ColorProfile::Monochrome, ColorProfile::Plain] {
+            assert_eq!(build_with(&mode_info, colors, true).to_plain(), " Ctrl +  ▶[p] PANE  Ctrl + <p> Select pane");
+            assert_eq!(build_with(&mode_info, colors, false).to_plain(), " Ctrl +  <p> PANE  Ctrl + <p> Select pane");
+        }
+        // The key is reversed on its own in color, the whole tile is without colors
+        assert!(build_with(&mode_info, ColorProfile::Color, true).to_ansi().contains("▶[\u{1b}[7mp"));
+        assert!(build_with(&mode_info, ColorProfile::Monochrome, true).to_ansi().contains("\u{1b}[1;7m ▶[p] \u{1b}[4mPANE"));
+    }
+
+    #[test]
+    fn keeps_layout_without_colors() {
+        let mode_info = pane_mode();
+
+        for color_blind_safe in [false, true] {
+            let color = build_with(&mode_info, ColorProfile::Color, color_blind_safe);
+            for colors in [ColorProfile::Monochrome, ColorProfile::Plain] {
+                let status = build_with(&mode_info, colors, color_blind_safe);
+                assert_eq!(status.len(), color.len());
+                assert_eq!(status.to_plain(), color.to_plain());
+                let ansi = status.to_ansi();
+                assert!(!ansi.contains("38;") && !ansi.contains("48;"), "{ansi:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use ansi_term::{Colour, Style};
+use strum::EnumString;
+use zellij_tile::prelude::*;
+
+use super::accents::NamedColor;
+use super::colored_elements::{ColoredElements, SegmentStyle};
+use super::contrast;
+use super::fonts::FontProfile;
+use super::key_format::KeyNotation;
+
+#[derive(Clone, Copy, Default, EnumString, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Theme {
+    /// Colors from the palette of the Zellij theme in use
+    #[default]
+    Zellij,
+    /// Catppuccin Mocha
+    Catppuccin,
+    /// Gruvbox dark
+    Gruvbox,
+    Nord,
+    Dracula,
+    SolarizedLight,
+    SolarizedDark,
+    /// Tokyo Night, night variant
+    TokyoNight,
+    /// Pure colors on black, with a contrast of at least 7:1 throughout
+    HighContrast,
+}
+
+// Colors a preset is made of, the roles follow those of the Zellij palette in `ColoredElements::color_elements`
+struct Preset {
+    background: Colour,
+    foreground: Colour,
+    // key shortcut tiles
+    selected: Colour,
+    unselected: Colour,
+    unselected_alternate: Colour,
+    shortcut: Colour,
+    // hints
+    modifier: Colour,
+    key: Colour,
+    // segments
+    accent: Colour,
+    warning: Colour,
+    critical: Colour,
+    // named colors, in the order of `NamedColor`
+    named: [Colour; 7],
+}
+
+const fn rgb(hex: u32) -> Colour {
+    Colour::RGB((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
+}
+
+fn style(foreground: Colour, background: Colour) -> Style {
+    Style::new().fg(foreground).on(background)
+}
+
+static CATPPUCCIN: Preset = Preset {
+    background: rgb(0x1e1e2e),
+    foreground: rgb(0xcdd6f4),
+    selected: rgb(0xa6e3a1),
+    unselected: rgb(0x9399b2),
+    unselected_alternate: rgb(0xbac2de),
+    shortcut: rgb(0xf38ba8),
+    modifier: rgb(0xfab387),
+    key: rgb(0xa6e3a1),
+    accent: rgb(0x89b4fa),
+    warning: rgb(0xf9e2af),
+    critical: rgb(0xf38ba8),
+    named: [rgb(0xf38ba8), rgb(0xa6e3a1), rgb(0xf9e2af), rgb(0x89b4fa), rgb(0xcba6f7), rgb(0x94e2d5), rgb(0xfab387)],
+};
+
+static GRUVBOX: Preset = Preset {
+    background: rgb(0x282828),
+    foreground: rgb(0xebdbb2),
+    selected: rgb(0xb8bb26),
+    unselected: rgb(0xa89984),
+    unselected_alternate: rgb(0xd5c4a1),
+    shortcut: rgb(0x9d0006),
+    modifier: rgb(0xfe8019),
+    key: rgb(0xb8bb26),
+    accent: rgb(0x83a598),
+    warning: rgb(0xfabd2f),
+    critical: rgb(0xfb4934),
+    named: [rgb(0xfb4934), rgb(0xb8bb26), rgb(0xfabd2f), rgb(0x83a598), rgb(0xd3869b), rgb(0x8ec07c), rgb(0xfe8019)],
+};
+
+static NORD: Preset = Preset {
+    background: rgb(0x2e3440),
+    foreground: rgb(0xeceff4),
+    selected: rgb(0xa3be8c),
+    unselected: rgb(0xd8dee9),
+    unselected_alternate: rgb(0x88c0d0),
+    shortcut: rgb(0xbf616a),
+    modifier: rgb(0xd08770),
+    key: rgb(0xa3be8c),
+    accent: rgb(0x81a1c1),
+    warning: rgb(0xebcb8b),
+    critical: rgb(0xbf616a),
+    named: [rgb(0xbf616a), rgb(0xa3be8c), rgb(0xebcb8b), rgb(0x81a1c1), rgb(0xb48ead), rgb(0x88c0d0), rgb(0xd08770)],
+};
+
+static DRACULA: Preset = Preset {
+    background: rgb(0x282a36),
+    foreground: rgb(0xf8f8f2),
+    selected: rgb(0x50fa7b),
+    unselected: rgb(0xbd93f9),
+    unselected_alternate: rgb(0x8be9fd),
+    shortcut: rgb(0xff5555),
+    modifier: rgb(0xffb86c),
+    key: rgb(0x50fa7b),
+    accent: rgb(0xff79c6),
+    warning: rgb(0xf1fa8c),
+    critical: rgb(0xff5555),
+    named: [rgb(0xff5555), rgb(0x50fa7b), rgb(0xf1fa8c), rgb(0xbd93f9), rgb(0xff79c6), rgb(0x8be9fd), rgb(0xffb86c)],
+};
+
+static SOLARIZED_LIGHT: Preset = Preset {
+    background: rgb(0xfdf6e3),
+    foreground: rgb(0x657b83),
+    selected: rgb(0x859900),
+    unselected: rgb(0x586e75),
+    unselected_alternate: rgb(0x073642),
+    shortcut: rgb(0xdc322f),
+    modifier: rgb(0xcb4b16),
+    key: rgb(0x859900),
+    accent: rgb(0x268bd2),
+    warning: rgb(0xb58900),
+    critical: rgb(0xdc322f),
+    named: [rgb(0xdc322f), rgb(0x859900), rgb(0xb58900), rgb(0x268bd2), rgb(0xd33682), rgb(0x2aa198), rgb(0xcb4b16)],
+};
+
+static SOLARIZED_DARK: Preset = Preset {
+    background: rgb(0x002b36),
+    foreground: rgb(0x839496),
+    selected: rgb(0x859900),
+    unselected: rgb(0x93a1a1),
+    unselected_alternate: rgb(0xeee8d5),
+    shortcut: rgb(0xdc322f),
+    modifier: rgb(0xcb4b16),
+    key: rgb(0x859900),
+    accent: rgb(0x268bd2),
+    warning: rgb(0xb58900),
+    critical: rgb(0xdc322f),
+    named: [rgb(0xdc322f), rgb(0x859900), rgb(0xb58900), rgb(0x268bd2), rgb(0xd33682), rgb(0x2aa198), rgb(0xcb4b16)],
+};
+
+static TOKYO_NIGHT: Preset = Preset {
+    background: rgb(0x1a1b26),
+    foreground: rgb(0xc0caf5),
+    selected: rgb(0x9ece6a),
+    unselected: rgb(0xa9b1d6),
+    unselected_alternate: rgb(0x7aa2f7),
+    shortcut: rgb(0xdb4b4b),
+    modifier: rgb(0xff9e64),
+    key: rgb(0x9ece6a),
+    accent: rgb(0x7aa2f7),
+    warning: rgb(0xe0af68),
+    critical: rgb(0xf7768e),
+    named: [rgb(0xf7768e), rgb(0x9ece6a), rgb(0xe0af68), rgb(0x7aa2f7), rgb(0xbb9af7), rgb(0x7dcfff), rgb(0xff9e64)],
+};
+
+static HIGH_CONTRAST: Preset = Preset {
+    background: rgb(0x000000),
+    foreground: rgb(0xffffff),
+    selected: rgb(0xffff00),
+    unselected: rgb(0xffffff),
+    unselected_alternate: rgb(0x00ffff),
+    shortcut: rgb(0x0000aa),
+    modifier: rgb(0xffff00),
+    key: rgb(0x00ff00),
+    accent: rgb(0x00ffff),
+    warning: rgb(0xffff00),
+    critical: rgb(0xff6e6e),
+    named: [rgb(0xff6e6e), rgb(0x00ff00), rgb(0xffff00), rgb(0x5c9cff), rgb(0xff66ff), rgb(0x00ffff), rgb(0xffa500)],
+};
+
+impl Theme {
+    fn preset(&self) -> Option<&'static Preset> {
+        match self {
+            Theme::Zellij => None,
+            Theme::Catppuccin => Some(&CATPPUCCIN),
+            Theme::Gruvbox => Some(&GRUVBOX),
+            Theme::Nord => Some(&NORD),
+            Theme::Dracula => Some(&DRACULA),
+            Theme::SolarizedLight => Some(&SOLARIZED_LIGHT),
+            Theme::SolarizedDark => Some(&SOLARIZED_DARK),
+            Theme::TokyoNight => Some(&TOKYO_NIGHT),
+            Theme::HighContrast => Some(&HIGH_CONTRAST),
+        }
+    }
+
+    /// Contrast enforced with the theme, `configured` unless the theme requires more
+    pub fn minimum_contrast(&self, configured: f64) -> f64 {
+        match self {
+            Theme::HighContrast => configured.max(contrast::HIGH_CONTRAST),
+            _ => configured,
+        }
+    }
+
+    /// Color called `name` in the theme, or in `palette` when following Zellij
+    pub fn named_colour(&self, palette: &Palette, name: NamedColor) -> Colour {
+        if let Some(preset) = self.preset() { return preset.named[name as usize]; }
+
+        let colour = match name {
+            NamedColor::Red => palette.red,
+            NamedColor::Green => palette.green,
+            NamedColor::Yellow => palette.yellow,
+            NamedColor::Blue => palette.blue,
+            NamedColor::Magenta => palette.magenta,
+            NamedColor::Cyan => palette.cyan,
+            NamedColor::Orange => palette.orange,
+        };
+        match colour {
+            PaletteColor::Rgb((r, g, b)) => Colour::RGB(r, g, b),
+            PaletteColor::EightBit(idx) => Colour::Fixed(idx),
+        }
+    }
+
+    /// Elements colored with the theme, or with `palette` when following Zellij
+    pub fn colored_elements(&self, palette: &Palette, fonts: FontProfile) -> ColoredElements {
+        match self.preset() {
+            Some(preset) => preset.colored_elements(fonts),
+            None => ColoredElements::color_elements(palette, fonts),
+        }
+    }
+}
+
+impl Preset {
+    fn tile(&self, tile: Colour) -> SegmentStyle {
+        SegmentStyle {
+            prefix_separator: style(self.background, tile),
+            char_left_separator: style(self.background, tile).bold(),
+            char_shortcut: style(self.shortcut, tile).bold(),
+            char_right_separator: style(self.background, tile).bold(),
+            styled_text: style(self.background, tile).bold(),
+            suffix_separator: style(tile, self.background),
+            marker: "",
+            brackets: ["<", ">"],
+        }
+    }
+
+    fn colored_elements(&self, fonts: FontProfile) -> ColoredElements {
+        let alternate = if fonts.alternate_tile_colors() { self.unselected_alternate } else { self.unselected };
+
+        ColoredElements {
+            superkey_prefix: style(self.foreground, self.background).bold(),
+            superkey_suffix_separator: style(self.background, self.background),
+            selected: SegmentStyle {
+                suffix_separator: style(self.selected, self.background).bold(),
+                ..self.tile(self.selected)
+            },
+            unselected: self.tile(self.unselected),
+            unselected_alternate: self.tile(alternate),
+            disabled: SegmentStyle {
+                prefix_separator: style(self.background, self.unselected),
+                char_left_separator: style(self.background, self.unselected).dimmed().italic(),
+                char_shortcut: style(self.background, self.unselected).dimmed().italic(),
+                char_right_separator: style(self.background, self.unselected).dimmed().italic(),
+                styled_text: style(self.background, self.unselected).dimmed().italic(),
+                suffix_separator: style(self.unselected, self.background),
+                marker: "",
+                brackets: ["<", ">"],
+            },
+            modifier: style(self.modifier, self.background).bold(),
+            key: style(self.key, self.background).bold(),
+            text: style(self.foreground, self.background),
+            filler: style(self.foreground, self.background),
+            segment: style(self.foreground, self.background),
+            segment_accent: style(self.accent, self.background).bold(),
+            segment_warning: style(self.warning, self.background).bold(),
+            segment_critical: style(self.critical, self.background).bold(),
+            notation: KeyNotation::default(),
+            fonts,
+        }
+    }
+}
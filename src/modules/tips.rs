@@ -0,0 +1,267 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+use zellij_tile::prelude::actions::{Action, SearchDirection};
+use zellij_tile::prelude::*;
+
+use super::config::Config;
+use super::keybind_index::KeybindIndex;
+
+// Plugin data directory, where seen tips are remembered across sessions
+static SEEN_TIPS_PATH: &str = "/data/seen_tips";
+
+pub struct Tip {
+    pub text: &'static str,
+    // Keys to press in sequence, starting from Normal mode
+    pub keys: Vec<Key>,
+}
+
+fn tip_definitions() -> Vec<(&'static str, InputMode, Vec<Action>)> {
+    let normal = Action::SwitchToMode(InputMode::Normal);
+
+    vec![
+        ("New pane", InputMode::Normal, vec![Action::NewPane(None, None)]),
+        ("Cycle swap layouts", InputMode::Normal, vec![Action::NextSwapLayout]),
+        ("Toggle floating panes", InputMode::Pane, vec![Action::ToggleFloatingPanes, normal.clone()]),
+        ("Embed or float the focused pane", InputMode::Pane, vec![Action::TogglePaneEmbedOrFloating, normal.clone()]),
+        ("Toggle pane frames", InputMode::Pane, vec![Action::TogglePaneFrames, normal.clone()]),
+        ("Toggle fullscreen", InputMode::Pane, vec![Action::ToggleFocusFullscreen, normal.clone()]),
+        (
+            "Rename pane",
+            InputMode::Pane,
+            vec![Action::SwitchToMode(InputMode::RenamePane), Action::PaneNameInput(vec![0])],
+        ),
+        (
+            "Rename tab",
+            InputMode::Tab,
+            vec![Action::SwitchToMode(InputMode::RenameTab), Action::TabNameInput(vec![0])],
+        ),
+        ("Sync input to all panes in tab", InputMode::Tab, vec![Action::ToggleActiveSyncTab, normal.clone()]),
+        ("Break pane into a new tab", InputMode::Tab, vec![Action::BreakPane, normal.clone()]),
+        ("Edit scrollback in your editor", InputMode::Scroll, vec![Action::EditScrollback, normal]),
+        (
+            "Search scrollback",
+            InputMode::Scroll,
+            vec![Action::SwitchToMode(InputMode::EnterSearch), Action::SearchInput(vec![0])],
+        ),
+        ("Search upwards", InputMode::Search, vec![Action::Search(SearchDirection::Up)]),
+        ("Detach from the session", InputMode::Session, vec![Action::Detach]),
+    ]
+}
+
+/// Tips which can be carried out with the current keybinds
+fn available_tips(keybind_index: &KeybindIndex) -> Vec<Tip> {
+    let normal_keybinds = keybind_index.mode(InputMode::Normal);
+
+    tip_definitions()
+        .into_iter()
+        .filter_map(|(text, mode, actions)| {
+            let key = keybind_index.mode(mode).key(&actions)?;
+            let keys = if mode == InputMode::Normal {
+                vec![key]
+            } else {
+                // Switch to the tip's mode first
+                vec![normal_keybinds.key(&[Action::SwitchToMode(mode)])?, key]
+            };
+
+            Some(Tip { text, keys })
+        })
+        .collect()
+}
+
+#[derive(Default)]
+pub struct Tips {
+    enabled: bool,
+    interval: f64,
+    max_views: usize,
+    elapsed: f64,
+    // Whether the current tip was drawn on the last render
+    drawn: bool,
+    index: usize,
+    views: BTreeMap<&'static str, usize>,
+    seen: BTreeSet<String>,
+}
+
+impl Tips {
+    pub fn new(config: &Config) -> Self {
+        let seen = match fs::read_to_string(SEEN_TIPS_PATH) {
+            Ok(seen) => seen.lines().map(ToString::to_string).collect(),
+            Err(_) => BTreeSet::new(),
+        };
+
+        Tips {
+            enabled: config.tips,
+            interval: config.tips_interval,
+            max_views: config.tips_max_views,
+            elapsed: 0.0,
+            drawn: false,
+            index: 0,
+            views: BTreeMap::new(),
+            seen,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn unseen_tips(&self, keybind_index: &KeybindIndex) -> Vec<Tip> {
+        let mut tips = available_tips(keybind_index);
+        tips.retain(|tip| !self.seen.contains(tip.text));
+        tips
+    }
+
+    pub fn current(&self, keybind_index: &KeybindIndex) -> Option<Tip> {
+        if !self.enabled { return None; }
+
+        let mut tips = self.unseen_tips(keybind_index);
+        if tips.is_empty() { return None; }
+
+        let index = self.index % tips.len();
+        Some(tips.swap_remove(index))
+    }
+
+    /// Records whether the current tip was drawn, e.g. it isn't while a notification is shown
+    pub fn set_drawn(&mut self, drawn: bool) {
+        self.drawn = drawn;
+    }
+
+    pub fn tick(&mut self, secs: f64, keybind_index: &KeybindIndex) -> bool {
+        if !self.enabled { return false; }
+
+        self.elapsed += secs;
+        if self.elapsed < self.interval { return false; }
+        self.elapsed = 0.0;
+
+        let Some(tip) = self.current(keybind_index) else { return false };
+        // Only tips the user got to see count as viewed, others are rotated without counting, e.g. a shorter one may fit
+        if !self.drawn {
+            self.index += 1;
+            return true;
+        }
+
+        // Mark the tip as seen once it has been shown often enough
+        let views = self.views.entry(tip.text).or_default();
+        *views += 1;
+        if *views >= self.max_views {
+            self.seen.insert(tip.text.to_string());
+            let seen = self.seen.iter().cloned().collect::<Vec<String>>().join("\n");
+            let _ = fs::write(SEEN_TIPS_PATH, seen);
+        } else {
+            self.index += 1;
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use zellij_tile::prelude::actions::Action;
+    use zellij_tile::prelude::*;
+
+    use super::{available_tips, Tips};
+    use crate::modules::keybind_index::KeybindIndex;
+
+    fn keybind_index(keybinds: KeybindsVec) -> KeybindIndex {
+        KeybindIndex::new(&ModeInfo {
+            keybinds,
+            ..ModeInfo::default()
+        })
+    }
+
+    // New pane with Alt+n, and floating panes toggled with Ctrl+p then w
+    fn two_tips() -> KeybindIndex {
+        let normal = Action::SwitchToMode(InputMode::Normal);
+
+        keybind_index(vec![
+            (InputMode::Normal, vec![
+                (Key::Alt(CharOrArrow::Char('n')), vec![Action::NewPane(None, None)]),
+                (Key::Ctrl('p'), vec![Action::SwitchToMode(InputMode::Pane)]),
+            ]),
+            (InputMode::Pane, vec![
+                (Key::Char('w'), vec![Action::ToggleFloatingPanes, normal.clone()]),
+                // Can't be reached from Normal mode without a key to Tab mode
+                (Key::Char('b'), vec![Action::BreakPane, normal]),
+            ]),
+        ])
+    }
+
+    fn tips(max_views: usize) -> Tips {
+        Tips {
+            enabled: true,
+            interval: 10.0,
+            max_views,
+            elapsed: 0.0,
+            drawn: true,
+            index: 0,
+            views: BTreeMap::new(),
+            seen: BTreeSet::new(),
+        }
+    }
+
+    fn current_text(tips: &Tips, keybind_index: &KeybindIndex) -> Option<&'static str> {
+        tips.current(keybind_index).map(|tip| tip.text)
+    }
+
+    #[test]
+    fn offers_tips_reachable_from_normal_mode() {
+        let tips = available_tips(&two_tips());
+
+        assert_eq!(tips.iter().map(|tip| tip.text).collect::<Vec<_>>(), ["New pane", "Toggle floating panes"]);
+        assert_eq!(tips[0].keys, [Key::Alt(CharOrArrow::Char('n'))]);
+        assert_eq!(tips[1].keys, [Key::Ctrl('p'), Key::Char('w')]);
+    }
+
+    #[test]
+    fn rotates_tips_every_interval() {
+        let (mut tips, keybind_index) = (tips(10), two_tips());
+        assert_eq!(current_text(&tips, &keybind_index), Some("New pane"));
+
+        assert!(!tips.tick(9.0, &keybind_index));
+        assert_eq!(current_text(&tips, &keybind_index), Some("New pane"));
+        assert!(tips.tick(1.0, &keybind_index));
+        assert_eq!(current_text(&tips, &keybind_index), Some("Toggle floating panes"));
+        assert!(tips.tick(10.0, &keybind_index));
+        assert_eq!(current_text(&tips, &keybind_index), Some("New pane"));
+        assert_eq!(tips.views.get("New pane"), Some(&1));
+        assert_eq!(tips.views.get("Toggle floating panes"), Some(&1));
+    }
+
+    #[test]
+    fn counts_only_drawn_tips_as_viewed() {
+        let (mut tips, keybind_index) = (tips(10), two_tips());
+        tips.set_drawn(false);
+
+        assert!(tips.tick(10.0, &keybind_index));
+        assert_eq!(current_text(&tips, &keybind_index), Some("Toggle floating panes"));
+        assert!(tips.views.is_empty());
+    }
+
+    #[test]
+    fn retires_tips_viewed_often_enough() {
+        let (mut tips, keybind_index) = (tips(1), two_tips());
+
+        assert!(tips.tick(10.0, &keybind_index));
+        assert!(tips.seen.contains("New pane"));
+        assert_eq!(current_text(&tips, &keybind_index), Some("Toggle floating panes"));
+        assert!(tips.tick(10.0, &keybind_index));
+        assert_eq!(current_text(&tips, &keybind_index), None);
+        assert!(!tips.tick(10.0, &keybind_index));
+    }
+
+    #[test]
+    fn shows_nothing_without_tips() {
+        let (mut tips, keybind_index) = (tips(10), keybind_index(vec![]));
+
+        assert!(available_tips(&keybind_index).is_empty());
+        assert_eq!(current_text(&tips, &keybind_index), None);
+        assert!(!tips.tick(10.0, &keybind_index));
+
+        let mut disabled = Tips { enabled: false, ..tips };
+        assert_eq!(current_text(&disabled, &two_tips()), None);
+        assert!(!disabled.tick(10.0, &two_tips()));
+    }
+}
@@ -1,127 +1,96 @@
 use zellij_tile::prelude::actions::{Action, SearchDirection, SearchOption};
 use zellij_tile::prelude::*;
 
-pub fn filter_get_superkey(entry: &(Key, Vec<Action>)) -> Option<&'static str> {
-    match entry.1.first() {
-        // No actions defined, ignore
-        None => None,
-        Some(action) => {
-            let key = entry.0;
+use super::keybind_index::ModeKeybinds;
 
-            // Ignore certain "default" keybindings that switch back to normal InputMode.
-            // These include: ' ', '\n', 'Esc'
-            if matches!(key, Key::Char(' ' | '\n') | Key::Esc) {
-                return None;
-            }
-
-            let is_mode_switch_or_quit_action = match action {
-                // `SwitchToMode` action
-                Action::SwitchToMode(mode) => {
-                    matches!(
-                        mode,
-                        InputMode::Normal
-                            | InputMode::Locked
-                            | InputMode::Pane
-                            | InputMode::Tab
-                            | InputMode::Resize
-                            | InputMode::Move
-                            | InputMode::Search
-                            | InputMode::Scroll
-                            | InputMode::Session
-                            | InputMode::Tmux
-                    )
-                }
-                // `Quit` action
-                Action::Quit => true,
-                // Not a `SwitchToMode` or `Quit` action, ignore
-                _ => false,
-            };
+/// Pane focused in the active tab, among the floating panes when they are shown
+pub fn focused_pane<'a>(tabs: &[TabInfo], panes: &'a PaneManifest) -> Option<&'a PaneInfo> {
+    let tab = tabs.iter().find(|tab| tab.active)?;
 
-            if is_mode_switch_or_quit_action {
-                match key {
-                    Key::Ctrl(_) => Some("Ctrl"),
-                    Key::Alt(_) => Some("Alt"),
-                    _ => None,
-                }
-            } else {
-                None
-            }
-        }
-    }
+    panes.panes.get(&tab.position)?.iter().find(|pane| {
+        pane.is_focused && !pane.is_suppressed && pane.is_floating == tab.are_floating_panes_visible
+    })
 }
 
-fn action_key(keybinds: &[(Key, Vec<Action>)], action: &[Action]) -> Vec<Key> {
-    keybinds
-        .iter()
-        // Get keybinds which match specified action
-        .filter_map(|(key, actions)| if actions.as_slice() == action { Some(*key) } else { None })
-        .collect::<Vec<Key>>()
+fn action_key(km: &ModeKeybinds, action: &[Action]) -> Vec<Key> {
+    km.hint_key(action).into_iter().collect()
 }
 
-fn action_key_group(keybinds: &[(Key, Vec<Action>)], actions: &[&[Action]]) -> Vec<Key> {
+fn action_key_group(km: &ModeKeybinds, actions: &[&[Action]]) -> Vec<Key> {
     let mut ret = vec![];
 
     for &action in actions {
-        ret.extend(action_key(keybinds, action));
+        ret.extend(action_key(km, action));
     }
 
     ret
 }
 
-pub fn to_key(keybinds: &[(Key, Vec<Action>)], action: &[Action]) -> Option<Key> {
-    action_key(keybinds, action)
-        .into_iter()
-        // Get the first Key which is not the "default" keybindings: ' ', '\n', 'Esc'
-        .find(|key| !matches!(key, Key::Char(' ' | '\n') | Key::Esc))
-}
-
-pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>)> {
-    let mut old_keymap = mode_info.get_mode_keybinds();
+pub fn get_keys_and_hints(mode: InputMode, km: &ModeKeybinds) -> Vec<(String, String, Vec<Key>)> {
     let s = ToString::to_string;
 
-    // Find a keybinding to get back to "Normal" input mode, before keymap deduplication below.
-    // Prefer '\n' over other choices.
-    let to_normal_keys = action_key(&old_keymap, &[Action::SwitchToMode(InputMode::Normal)]);
-    let to_normal_key = if to_normal_keys.contains(&Key::Char('\n')) {
-        vec![Key::Char('\n')]
-    } else {
-        // Take the first key, if possible
-        to_normal_keys.into_iter().take(1).collect()
-    };
-
-    // Sort and deduplicate the keybindings first.
-    // Sort after the `Key`s, and deduplicate by their `Action` vectors.
-    // An unstable sort is fine here because if the user maps anything to the same key again, anything can happen...
-    old_keymap.sort_unstable_by(|(key_a, _), (key_b, _)| key_a.partial_cmp(key_b).unwrap());
+    // Every keybinding to get back to "Normal" input mode, and the one preferred among them
+    let to_normal_keys = km.keys(&[Action::SwitchToMode(InputMode::Normal)]).to_vec();
+    let to_normal_key = km.to_normal_key().into_iter().collect::<Vec<Key>>();
 
-    let mut known_actions = Vec::<Vec<Action>>::new();
-    let mut km = Vec::<(Key, Vec<Action>)>::new();
-    for (key, actions) in old_keymap {
-        if !known_actions.contains(&actions) {
-            known_actions.push(actions.clone());
-            km.push((key, actions));
-        } else if *actions.as_slice() == [Action::GoToNextTab] && key == Key::Right {
-            // Modify known key-action only if it's GoToNextTab action
-            // Assumption: If Key::Right is configured for GoToNextTab, assume Key::Left is also configured for GoToPreviousTab
-            km.retain(|(_, a)| *a.as_slice() != [Action::GoToNextTab]);
-            km.push((key, actions));
+    match mode {
+        InputMode::Normal => {
+            vec![
+                (s("New pane"), s("New"), action_key(km, &[Action::NewPane(None, None)])),
+                (
+                    s("Move focus"),
+                    s("Move"),
+                    action_key_group(
+                        km,
+                        &[
+                            &[Action::MoveFocusOrTab(Direction::Left)],
+                            &[Action::MoveFocus(Direction::Left)],
+                            &[Action::MoveFocus(Direction::Down)],
+                            &[Action::MoveFocus(Direction::Up)],
+                            &[Action::MoveFocusOrTab(Direction::Right)],
+                            &[Action::MoveFocus(Direction::Right)],
+                        ],
+                    ),
+                ),
+                (
+                    s("Increase/Decrease size"),
+                    s("Resize"),
+                    action_key_group(
+                        km,
+                        &[
+                            &[Action::Resize(Resize::Increase, None)],
+                            &[Action::Resize(Resize::Decrease, None)],
+                        ],
+                    ),
+                ),
+                (
+                    s("Swap layouts"),
+                    s("Swap"),
+                    action_key_group(km, &[&[Action::PreviousSwapLayout], &[Action::NextSwapLayout]]),
+                ),
+                (s("Toggle Floating"), s("Floating"), action_key(km, &[Action::ToggleFloatingPanes])),
+            ]
+        }
+        InputMode::Locked => {
+            let mut keys_and_hints = vec![(s("-- INTERFACE LOCKED --"), s("INTERFACE LOCKED"), vec![])];
+            // Show every key which leaves Locked mode, if any
+            if !to_normal_keys.is_empty() {
+                keys_and_hints.push((s("Unlock"), s("Unlock"), to_normal_keys));
+            }
+            keys_and_hints
         }
-    }
-
-    match mode_info.mode {
-        InputMode::Locked => vec![(s("-- INTERFACE LOCKED --"), s("INTERFACE LOCKED"), vec![])],
         InputMode::Pane => {
             vec![
                 (
                     s("New"),
                     s("New"),
-                    action_key(&km, &[Action::NewPane(None, None), Action::SwitchToMode(InputMode::Normal)]),
+                    action_key(km, &[Action::NewPane(None, None), Action::SwitchToMode(InputMode::Normal)]),
                 ),
                 (
                     s("Move focus"),
                     s("Move"),
                     action_key_group(
-                        &km,
+                        km,
                         &[
                             &[Action::MoveFocus(Direction::Left)],
                             &[Action::MoveFocus(Direction::Down)],
@@ -133,18 +102,18 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                 (
                     s("Close"),
                     s("Close"),
-                    action_key(&km, &[Action::CloseFocus, Action::SwitchToMode(InputMode::Normal)]),
+                    action_key(km, &[Action::CloseFocus, Action::SwitchToMode(InputMode::Normal)]),
                 ),
                 (
                     s("Rename"),
                     s("Rename"),
-                    action_key(&km, &[Action::SwitchToMode(InputMode::RenamePane), Action::PaneNameInput(vec![0])]),
+                    action_key(km, &[Action::SwitchToMode(InputMode::RenamePane), Action::PaneNameInput(vec![0])]),
                 ),
                 (
                     s("Split down"),
                     s("Down"),
                     action_key(
-                        &km,
+                        km,
                         &[
                             Action::NewPane(Some(Direction::Down), None),
                             Action::SwitchToMode(InputMode::Normal),
@@ -155,7 +124,7 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                     s("Split right"),
                     s("Right"),
                     action_key(
-                        &km,
+                        km,
                         &[
                             Action::NewPane(Some(Direction::Right), None),
                             Action::SwitchToMode(InputMode::Normal),
@@ -165,24 +134,24 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                 (
                     s("Toggle Fullscreen"),
                     s("Fullscreen"),
-                    action_key(&km, &[Action::ToggleFocusFullscreen, Action::SwitchToMode(InputMode::Normal)]),
+                    action_key(km, &[Action::ToggleFocusFullscreen, Action::SwitchToMode(InputMode::Normal)]),
                 ),
                 (
                     s("Toggle Frames"),
                     s("Frames"),
-                    action_key(&km, &[Action::TogglePaneFrames, Action::SwitchToMode(InputMode::Normal)]),
+                    action_key(km, &[Action::TogglePaneFrames, Action::SwitchToMode(InputMode::Normal)]),
                 ),
                 (
                     s("Toggle Floating"),
                     s("Floating"),
-                    action_key(&km, &[Action::ToggleFloatingPanes, Action::SwitchToMode(InputMode::Normal)]),
+                    action_key(km, &[Action::ToggleFloatingPanes, Action::SwitchToMode(InputMode::Normal)]),
                 ),
                 (
                     s("Toggle Embed"),
                     s("Embed"),
-                    action_key(&km, &[Action::TogglePaneEmbedOrFloating, Action::SwitchToMode(InputMode::Normal)]),
+                    action_key(km, &[Action::TogglePaneEmbedOrFloating, Action::SwitchToMode(InputMode::Normal)]),
                 ),
-                (s("Next"), s("Next"), action_key(&km, &[Action::SwitchFocus])),
+                (s("Next"), s("Next"), action_key(km, &[Action::SwitchFocus])),
                 (s("Select pane"), s("Select"), to_normal_key),
             ]
         }
@@ -192,7 +161,7 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                     s("New"),
                     s("New"),
                     action_key(
-                        &km,
+                        km,
                         &[
                             Action::NewTab(None, vec![], None, None, None),
                             Action::SwitchToMode(InputMode::Normal),
@@ -202,40 +171,40 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                 (
                     s("Change Focus"),
                     s("Move"),
-                    action_key_group(&km, &[&[Action::GoToPreviousTab], &[Action::GoToNextTab]]),
+                    action_key_group(km, &[&[Action::GoToPreviousTab], &[Action::GoToNextTab]]),
                 ),
                 (
                     s("Close"),
                     s("Close"),
-                    action_key(&km, &[Action::CloseTab, Action::SwitchToMode(InputMode::Normal)]),
+                    action_key(km, &[Action::CloseTab, Action::SwitchToMode(InputMode::Normal)]),
                 ),
                 (
                     s("Rename"),
                     s("Rename"),
-                    action_key(&km, &[Action::SwitchToMode(InputMode::RenameTab), Action::TabNameInput(vec![0])]),
+                    action_key(km, &[Action::SwitchToMode(InputMode::RenameTab), Action::TabNameInput(vec![0])]),
                 ),
                 (
                     s("Sync"),
                     s("Sync"),
-                    action_key(&km, &[Action::ToggleActiveSyncTab, Action::SwitchToMode(InputMode::Normal)]),
+                    action_key(km, &[Action::ToggleActiveSyncTab, Action::SwitchToMode(InputMode::Normal)]),
                 ),
                 (
                     s("Break pane to new tab"),
                     s("Break out"),
-                    action_key(&km, &[Action::BreakPane, Action::SwitchToMode(InputMode::Normal)]),
+                    action_key(km, &[Action::BreakPane, Action::SwitchToMode(InputMode::Normal)]),
                 ),
                 (
                     s("Break pane left/right"),
                     s("Break"),
                     action_key_group(
-                        &km,
+                        km,
                         &[
                             &[Action::BreakPaneLeft, Action::SwitchToMode(InputMode::Normal)],
                             &[Action::BreakPaneRight, Action::SwitchToMode(InputMode::Normal)],
                         ],
                     ),
                 ),
-                (s("Toggle"), s("Toggle"), action_key(&km, &[Action::ToggleTab])),
+                (s("Toggle"), s("Toggle"), action_key(km, &[Action::ToggleTab])),
                 (s("Select tab"), s("Select"), to_normal_key),
             ]
         }
@@ -245,7 +214,7 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                     s("Increase/Decrease size"),
                     s("Increase/Decrease"),
                     action_key_group(
-                        &km,
+                        km,
                         &[
                             &[Action::Resize(Resize::Increase, None)],
                             &[Action::Resize(Resize::Decrease, None)],
@@ -256,7 +225,7 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                     s("Increase to"),
                     s("Increase"),
                     action_key_group(
-                        &km,
+                        km,
                         &[
                             &[Action::Resize(Resize::Increase, Some(Direction::Left))],
                             &[Action::Resize(Resize::Increase, Some(Direction::Down))],
@@ -269,7 +238,7 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                     s("Decrease from"),
                     s("Decrease"),
                     action_key_group(
-                        &km,
+                        km,
                         &[
                             &[Action::Resize(Resize::Decrease, Some(Direction::Left))],
                             &[Action::Resize(Resize::Decrease, Some(Direction::Down))],
@@ -287,7 +256,7 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                     s("Switch Location"),
                     s("Move"),
                     action_key_group(
-                        &km,
+                        km,
                         &[
                             &[Action::MovePane(Some(Direction::Left))],
                             &[Action::MovePane(Some(Direction::Down))],
@@ -296,7 +265,7 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                         ],
                     ),
                 ),
-                (s("Next pane"), s("Next"), action_key(&km, &[Action::MovePane(None)])),
+                (s("Next pane"), s("Next"), action_key(km, &[Action::MovePane(None)])),
             ]
         }
         InputMode::Scroll => {
@@ -304,34 +273,34 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                 (
                     s("Enter search term"),
                     s("Search"),
-                    action_key(&km, &[Action::SwitchToMode(InputMode::EnterSearch), Action::SearchInput(vec![0])]),
+                    action_key(km, &[Action::SwitchToMode(InputMode::EnterSearch), Action::SearchInput(vec![0])]),
                 ),
-                (s("Scroll"), s("Scroll"), action_key_group(&km, &[&[Action::ScrollDown], &[Action::ScrollUp]])),
+                (s("Scroll"), s("Scroll"), action_key_group(km, &[&[Action::ScrollDown], &[Action::ScrollUp]])),
                 (
                     s("Scroll page"),
                     s("Scroll"),
-                    action_key_group(&km, &[&[Action::PageScrollDown], &[Action::PageScrollUp]]),
+                    action_key_group(km, &[&[Action::PageScrollDown], &[Action::PageScrollUp]]),
                 ),
                 (
                     s("Scroll half page"),
                     s("Scroll"),
-                    action_key_group(&km, &[&[Action::HalfPageScrollDown], &[Action::HalfPageScrollUp]]),
+                    action_key_group(km, &[&[Action::HalfPageScrollDown], &[Action::HalfPageScrollUp]]),
                 ),
                 (
                     s("Edit scrollback in default editor"),
                     s("Edit"),
-                    action_key(&km, &[Action::EditScrollback, Action::SwitchToMode(InputMode::Normal)]),
+                    action_key(km, &[Action::EditScrollback, Action::SwitchToMode(InputMode::Normal)]),
                 ),
                 (s("Select pane"), s("Select"), to_normal_key),
             ]
         }
         InputMode::EnterSearch => {
             vec![
-                (s("When done"), s("Done"), action_key(&km, &[Action::SwitchToMode(InputMode::Search)])),
+                (s("When done"), s("Done"), action_key(km, &[Action::SwitchToMode(InputMode::Search)])),
                 (
                     s("Cancel"),
                     s("Cancel"),
-                    action_key(&km, &[Action::SearchInput(vec![27]), Action::SwitchToMode(InputMode::Scroll)]),
+                    action_key(km, &[Action::SearchInput(vec![27]), Action::SwitchToMode(InputMode::Scroll)]),
                 ),
             ]
         }
@@ -340,42 +309,42 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                 (
                     s("Enter Search term"),
                     s("Search"),
-                    action_key(&km, &[Action::SwitchToMode(InputMode::EnterSearch), Action::SearchInput(vec![0])]),
+                    action_key(km, &[Action::SwitchToMode(InputMode::EnterSearch), Action::SearchInput(vec![0])]),
                 ),
-                (s("Scroll"), s("Scroll"), action_key_group(&km, &[&[Action::ScrollDown], &[Action::ScrollUp]])),
+                (s("Scroll"), s("Scroll"), action_key_group(km, &[&[Action::ScrollDown], &[Action::ScrollUp]])),
                 (
                     s("Scroll page"),
                     s("Scroll"),
-                    action_key_group(&km, &[&[Action::PageScrollDown], &[Action::PageScrollUp]]),
+                    action_key_group(km, &[&[Action::PageScrollDown], &[Action::PageScrollUp]]),
                 ),
                 (
                     s("Scroll half page"),
                     s("Scroll"),
-                    action_key_group(&km, &[&[Action::HalfPageScrollDown], &[Action::HalfPageScrollUp]]),
+                    action_key_group(km, &[&[Action::HalfPageScrollDown], &[Action::HalfPageScrollUp]]),
                 ),
-                (s("Search down"), s("Down"), action_key(&km, &[Action::Search(SearchDirection::Down)])),
-                (s("Search up"), s("Up"), action_key(&km, &[Action::Search(SearchDirection::Up)])),
+                (s("Search down"), s("Down"), action_key(km, &[Action::Search(SearchDirection::Down)])),
+                (s("Search up"), s("Up"), action_key(km, &[Action::Search(SearchDirection::Up)])),
                 (
                     s("Case sensitive"),
                     s("Case"),
-                    action_key(&km, &[Action::SearchToggleOption(SearchOption::CaseSensitivity)]),
+                    action_key(km, &[Action::SearchToggleOption(SearchOption::CaseSensitivity)]),
                 ),
-                (s("Wrap"), s("Wrap"), action_key(&km, &[Action::SearchToggleOption(SearchOption::Wrap)])),
+                (s("Wrap"), s("Wrap"), action_key(km, &[Action::SearchToggleOption(SearchOption::Wrap)])),
                 (
                     s("Whole words"),
                     s("Whole"),
-                    action_key(&km, &[Action::SearchToggleOption(SearchOption::WholeWord)]),
+                    action_key(km, &[Action::SearchToggleOption(SearchOption::WholeWord)]),
                 ),
             ]
         }
         InputMode::Session => {
             vec![
-                (s("Detach"), s("Detach"), action_key(&km, &[Action::Detach])),
+                (s("Detach"), s("Detach"), action_key(km, &[Action::Detach])),
                 (
                     s("Session Manager"),
                     s("Manager"),
                     action_key(
-                        &km,
+                        km,
                         &[
                             #[allow(clippy::default_trait_access)]
                             Action::LaunchOrFocusPlugin(Default::default(), true, true, false),
@@ -392,7 +361,7 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                     s("Move focus"),
                     s("Move"),
                     action_key_group(
-                        &km,
+                        km,
                         &[
                             &[Action::MoveFocus(Direction::Left)],
                             &[Action::MoveFocus(Direction::Down)],
@@ -405,7 +374,7 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                     s("Split down"),
                     s("Down"),
                     action_key(
-                        &km,
+                        km,
                         &[
                             Action::NewPane(Some(Direction::Down), None),
                             Action::SwitchToMode(InputMode::Normal),
@@ -416,7 +385,7 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                     s("Split right"),
                     s("Right"),
                     action_key(
-                        &km,
+                        km,
                         &[
                             Action::NewPane(Some(Direction::Right), None),
                             Action::SwitchToMode(InputMode::Normal),
@@ -426,13 +395,13 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                 (
                     s("Fullscreen"),
                     s("Fullscreen"),
-                    action_key(&km, &[Action::ToggleFocusFullscreen, Action::SwitchToMode(InputMode::Normal)]),
+                    action_key(km, &[Action::ToggleFocusFullscreen, Action::SwitchToMode(InputMode::Normal)]),
                 ),
                 (
                     s("New tab"),
                     s("New"),
                     action_key(
-                        &km,
+                        km,
                         &[
                             Action::NewTab(None, vec![], None, None, None),
                             Action::SwitchToMode(InputMode::Normal),
@@ -442,17 +411,17 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                 (
                     s("Rename tab"),
                     s("Rename"),
-                    action_key(&km, &[Action::SwitchToMode(InputMode::RenameTab), Action::TabNameInput(vec![0])]),
+                    action_key(km, &[Action::SwitchToMode(InputMode::RenameTab), Action::TabNameInput(vec![0])]),
                 ),
                 (
                     s("Previous Tab"),
                     s("Previous"),
-                    action_key(&km, &[Action::GoToPreviousTab, Action::SwitchToMode(InputMode::Normal)]),
+                    action_key(km, &[Action::GoToPreviousTab, Action::SwitchToMode(InputMode::Normal)]),
                 ),
                 (
                     s("Next Tab"),
                     s("Next"),
-                    action_key(&km, &[Action::GoToNextTab, Action::SwitchToMode(InputMode::Normal)]),
+                    action_key(km, &[Action::GoToNextTab, Action::SwitchToMode(InputMode::Normal)]),
                 ),
                 (s("Select pane"), s("Select"), to_normal_key),
             ]
@@ -464,7 +433,7 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                     s("Select pane"),
                     s("Select"),
                     action_key_group(
-                        &km,
+                        km,
                         &[
                             &[Action::MoveFocus(Direction::Left)],
                             &[Action::MoveFocus(Direction::Down)],
@@ -482,7 +451,7 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
                     s("Select tab"),
                     s("Select"),
                     action_key_group(
-                        &km,
+                        km,
                         &[
                             &[Action::MoveFocusOrTab(Direction::Left)],
                             &[Action::MoveFocusOrTab(Direction::Right)],
@@ -494,3 +463,91 @@ pub fn get_keys_and_hints(mode_info: &ModeInfo) -> Vec<(String, String, Vec<Key>
         _ => vec![],
     }
 }
+
+#[cfg(all(test, feature = "preview"))]
+mod tests {
+    use zellij_tile::prelude::actions::Action;
+    use zellij_tile::prelude::*;
+    use zellij_utils::input::config::Config as ZellijConfig;
+
+    use super::get_keys_and_hints;
+    use crate::modules::keybind_index::KeybindIndex;
+
+    fn hints(mode: InputMode, keybinds: KeybindsVec) -> Vec<(String, String, Vec<Key>)> {
+        let mode_info = ModeInfo {
+            mode,
+            keybinds,
+            ..ModeInfo::default()
+        };
+
+        get_keys_and_hints(mode, KeybindIndex::new(&mode_info).mode(mode))
+    }
+
+    fn default_hints(mode: InputMode) -> Vec<(String, String, Vec<Key>)> {
+        hints(mode, ZellijConfig::from_default_assets().unwrap().keybinds.to_keybinds_vec())
+    }
+
+    fn hint(long: &str, short: &str, keys: &[Key]) -> (String, String, Vec<Key>) {
+        (long.to_string(), short.to_string(), keys.to_vec())
+    }
+
+    #[test]
+    fn locked_mode_shows_unlock_keys() {
+        assert_eq!(default_hints(InputMode::Locked), [
+            hint("-- INTERFACE LOCKED --", "INTERFACE LOCKED", &[]),
+            hint("Unlock", "Unlock", &[Key::Ctrl('g')]),
+        ]);
+    }
+
+    #[test]
+    fn locked_mode_shows_every_unlock_key() {
+        let to_normal = vec![Action::SwitchToMode(InputMode::Normal)];
+        let keybinds = vec![(InputMode::Locked, vec![
+            (Key::Ctrl('g'), to_normal.clone()),
+            (Key::F(1), to_normal),
+            (Key::Char('q'), vec![Action::Quit]),
+        ])];
+
+        assert_eq!(hints(InputMode::Locked, keybinds), [
+            hint("-- INTERFACE LOCKED --", "INTERFACE LOCKED", &[]),
+            hint("Unlock", "Unlock", &[Key::Ctrl('g'), Key::F(1)]),
+        ]);
+    }
+
+    #[test]
+    fn locked_mode_without_unlock_key() {
+        let keybinds = vec![(InputMode::Locked, vec![(Key::Char('q'), vec![Action::Quit])])];
+
+        assert_eq!(hints(InputMode::Locked, keybinds), [hint("-- INTERFACE LOCKED --", "INTERFACE LOCKED", &[])]);
+    }
+
+    #[test]
+    fn normal_mode_shows_modeless_shortcuts() {
+        assert_eq!(default_hints(InputMode::Normal), [
+            hint("New pane", "New", &[Key::Alt(CharOrArrow::Char('n'))]),
+            hint("Move focus", "Move", &['h', 'j', 'k', 'l'].map(|c| Key::Alt(CharOrArrow::Char(c)))),
+            hint("Increase/Decrease size", "Resize", &['+', '-'].map(|c| Key::Alt(CharOrArrow::Char(c)))),
+            hint("Swap layouts", "Swap", &['[', ']'].map(|c| Key::Alt(CharOrArrow::Char(c)))),
+            // Not bound by default, so it isn't shown
+            hint("Toggle Floating", "Floating", &[]),
+        ]);
+    }
+
+    #[test]
+    fn normal_mode_follows_the_keymap() {
+        let keybinds = vec![(InputMode::Normal, vec![
+            (Key::Ctrl('n'), vec![Action::NewPane(None, None)]),
+            (Key::Alt(CharOrArrow::Direction(Direction::Left)), vec![Action::MoveFocus(Direction::Left)]),
+            (Key::Alt(CharOrArrow::Direction(Direction::Right)), vec![Action::MoveFocus(Direction::Right)]),
+            (Key::Alt(CharOrArrow::Char('f')), vec![Action::ToggleFloatingPanes]),
+        ])];
+
+        assert_eq!(hints(InputMode::Normal, keybinds), [
+            hint("New pane", "New", &[Key::Ctrl('n')]),
+            hint("Move focus", "Move", &[Direction::Left, Direction::Right].map(|d| Key::Alt(CharOrArrow::Direction(d)))),
+            hint("Increase/Decrease size", "Resize", &[]),
+            hint("Swap layouts", "Swap", &[]),
+            hint("Toggle Floating", "Floating", &[Key::Alt(CharOrArrow::Char('f'))]),
+        ]);
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::BTreeMap;
+
+use zellij_tile::prelude::*;
+
+use crate::modules::colored_elements::SegmentLevel;
+use crate::modules::config::Config;
+use crate::modules::keybind_index::KeybindIndex;
+use crate::modules::notification::{self, Notifications};
+use crate::modules::render_cache::RenderCache;
+use crate::modules::segments::Segments;
+use crate::modules::status_line::StatusLine;
+use crate::modules::tips::Tips;
+use crate::modules::utils;
+
+static TIMER_INTERVAL: f64 = 1.0;
+
+/// Names of the active tab and of the focused pane, as they were before renaming them
+#[derive(Default)]
+struct OriginalNames {
+    // Along with the position of the tab and the id of the pane they were recorded for
+    tab: Option<(usize, String)>,
+    pane: Option<((u32, bool), String)>,
+}
+
+impl OriginalNames {
+    // Zellij clears names when renaming starts, so names are recorded beforehand, skipping cleared ones. Names recorded
+    // for another tab or pane are dropped, as they would be shown as the original names of the active ones.
+    fn record(&mut self, tab: Option<&TabInfo>, pane: Option<&PaneInfo>) {
+        if let Some(tab) = tab {
+            if !tab.name.is_empty() {
+                self.tab = Some((tab.position, tab.name.clone()));
+            } else if self.tab.as_ref().is_some_and(|(position, _)| *position != tab.position) {
+                self.tab = None;
+            }
+        }
+        if let Some(pane) = pane {
+            let id = (pane.id, pane.is_plugin);
+            if !pane.title.is_empty() {
+                self.pane = Some((id, pane.title.clone()));
+            } else if self.pane.as_ref().is_some_and(|(recorded, _)| *recorded != id) {
+                self.pane = None;
+            }
+        }
+    }
+
+    fn get(&self, mode: InputMode) -> Option<&str> {
+        match mode {
+            InputMode::RenameTab => self.tab.as_ref().map(|(_, name)| &name[..]),
+            InputMode::RenamePane => self.pane.as_ref().map(|(_, name)| &name[..]),
+            _ => None,
+        }
+    }
+}
+
+/// The oneliner plugin, registered by the binary with `register_plugin!`
+#[derive(Default)]
+pub struct State {
+    tabs: Vec<TabInfo>,
+    panes: PaneManifest,
+    mode_info: ModeInfo,
+    keybind_index: KeybindIndex,
+    config: Config,
+    segments: Segments,
+    notifications: Notifications,
+    tips: Tips,
+    timer_running: bool,
+    // Seconds spent in Locked mode so far
+    locked_for: f64,
+    original_names: OriginalNames,
+    render_cache: RenderCache,
+    // Bumped whenever the keybinds, or anything shown besides the mode and tabs, change
+    revision: u64,
+}
+
+impl State {
+    fn focused_pane(&self) -> Option<&PaneInfo> {
+        utils::focused_pane(&self.tabs, &self.panes)
+    }
+
+    fn is_reminding_locked(&self) -> bool {
+        self.mode_info.mode == InputMode::Locked && self.config.locked_reminder.is_some()
+    }
+
+    // Escalate the styling the longer the session stays locked
+    fn locked_level(&self) -> Option<SegmentLevel> {
+        let reminder = self.config.locked_reminder.filter(|_| self.mode_info.mode == InputMode::Locked)?;
+
+        if self.locked_for >= 2.0 * reminder {
+            Some(SegmentLevel::Critical)
+        } else if self.locked_for >= reminder {
+            Some(SegmentLevel::Warning)
+        } else {
+            None
+        }
+    }
+
+    // Keep the timer running as long as anything depends on it
+    fn schedule_timer(&mut self) {
+        self.timer_running = !self.segments.is_empty()
+            || self.notifications.is_active()
+            || self.is_reminding_locked()
+            || self.tips.is_enabled();
+        if self.timer_running {
+            set_timeout(TIMER_INTERVAL);
+        }
+    }
+}
+
+impl ZellijPlugin for State {
+    fn load(&mut self, configuration: BTreeMap<String, String>) {
+        self.config = Config::from_configuration(&configuration);
+        self.segments = Segments::new(&self.config);
+        self.notifications = Notifications::new(&self.config);
+        self.tips = Tips::new(&self.config);
+
+        let mut permissions = vec![PermissionType::ReadApplicationState];
+        let mut event_types = vec![
+            EventType::ModeUpdate,
+            EventType::TabUpdate,
+            EventType::PaneUpdate,
+            EventType::PermissionRequestResult,
+            EventType::Timer,
+            EventType::CustomMessage,
+        ];
+        if self.segments.runs_commands() {
+            // Segments are refreshed by running commands on the host
+            permissions.push(PermissionType::RunCommands);
+            event_types.push(EventType::RunCommandResult);
+        }
+        if self.notifications.watches_inbox() {
+            event_types.extend([EventType::FileSystemCreate, EventType::FileSystemUpdate]);
+        }
+
+        set_selectable(true);
+        request_permission(&permissions);
+        subscribe(&event_types);
+    }
+
+    fn update(&mut self, event: Event) -> bool {
+        let mut should_render = false;
+        let is_state_update = !matches!(event, Event::ModeUpdate(_) | Event::TabUpdate(_));
+        let is_focus_update = matches!(event, Event::TabUpdate(_) | Event::PaneUpdate(_));
+
+        match event {
+            Event::ModeUpdate(mode_info) => {
+                should_render = self.mode_info != mode_info;
+                if self.mode_info.mode != mode_info.mode {
+                    self.locked_for = 0.0;
+                }
+                if self.mode_info.keybinds != mode_info.keybinds {
+                    self.keybind_index = KeybindIndex::new(&mode_info);
+                    self.revision += 1;
+                }
+                self.mode_info = mode_info;
+            }
+            Event::TabUpdate(tabs) => {
+                should_render = self.tabs != tabs;
+                self.tabs = tabs;
+            }
+            Event::PaneUpdate(panes) => {
+                // Only the focused pane is shown, other panes change all the time, e.g. their titles
+                let previous = self.focused_pane().cloned();
+                self.panes = panes;
+                should_render = self.focused_pane() != previous.as_ref();
+            }
+            Event::PermissionRequestResult(_) => {
+                should_render = true;
+                set_selectable(false);
+                unsubscribe(&[EventType::PermissionRequestResult]);
+
+                // Segments can only be refreshed once permissions are granted
+                self.segments.refresh();
+                if !self.timer_running {
+                    self.schedule_timer();
+                }
+            }
+            Event::Timer(secs) => {
+                should_render = self.segments.tick(secs);
+                should_render |= self.notifications.tick(secs);
+                should_render |= self.tips.tick(secs, &self.keybind_index);
+                if self.is_reminding_locked() {
+                    let level = self.locked_level();
+                    self.locked_for += secs;
+                    should_render |= level != self.locked_level();
+                }
+                self.schedule_timer();
+            }
+            Event::RunCommandResult(exit_code, stdout, _, context) => {
+                should_render = self.segments.update(exit_code, &stdout, &context);
+            }
+            Event::CustomMessage(name, payload) if name == notification::MESSAGE_NAME => {
+                should_render = self.notifications.push(&payload);
+            }
+            Event::FileSystemCreate(paths) | Event::FileSystemUpdate(paths) => {
+                should_render = self.notifications.read_inbox(&paths);
+            }
+            _ => {}
+        }
+
+        // The git segment follows the focused terminal pane
+        if is_focus_update {
+            let focused_pane = self.tabs.iter().find(|tab| tab.active).and_then(|tab| get_focused_pane(tab.position, &self.panes));
+            self.segments.focus(focused_pane.as_ref());
+        }
+
+        if !matches!(self.mode_info.mode, InputMode::RenameTab | InputMode::RenamePane) {
+            let active_tab = self.tabs.iter().find(|tab| tab.active);
+            self.original_names.record(active_tab, utils::focused_pane(&self.tabs, &self.panes));
+        }
+
+        if should_render && is_state_update {
+            self.revision += 1;
+        }
+
+        // Notifications need the timer to expire, and the locked reminder to escalate
+        if !self.timer_running && (self.notifications.is_active() || self.is_reminding_locked()) {
+            self.schedule_timer();
+        }
+
+        should_render
+    }
+
+    fn render(&mut self, _rows: usize, cols: usize) {
+        let escalation = self.locked_level();
+        let focused_pane = utils::focused_pane(&self.tabs, &self.panes);
+        let original_name = self.original_names.get(self.mode_info.mode);
+        let mut shows_tip = None;
+        let status = self.render_cache.get_or_render(&self.mode_info, &self.tabs, cols, self.revision, || {
+            let status = StatusLine::builder(&self.mode_info)
+                .keybind_index(&self.keybind_index)
+                .config(&self.config)
+                .width(cols)
+                .segments(&self.segments)
+                .notifications(&self.notifications)
+                .tip(self.tips.current(&self.keybind_index))
+                .escalation(escalation)
+                .focused_pane(focused_pane)
+                .tabs(&self.tabs)
+                .original_name(original_name)
+                .build();
+            shows_tip = Some(status.shows_tip());
+            status
+        });
+        print!("{status}");
+
+        // Cached lines show the same tip as when they were built
+        if let Some(shows_tip) = shows_tip {
+            self.tips.set_drawn(shows_tip);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zellij_tile::prelude::*;
+
+    use super::OriginalNames;
+    use crate::modules::status_line::StatusLine;
+
+    fn tab(position: usize, name: &str) -> TabInfo {
+        TabInfo {
+            position,
+            name: name.to_string(),
+            active: true,
+            ..TabInfo::default()
+        }
+    }
+
+    fn pane(id: u32, title: &str) -> PaneInfo {
+        PaneInfo {
+            id,
+            title: title.to_string(),
+            is_focused: true,
+            ..PaneInfo::default()
+        }
+    }
+
+    #[test]
+    fn gives_names_in_rename_modes_only() {
+        let mut names = OriginalNames::default();
+        names.record(Some(&tab(0, "logs")), Some(&pane(1, "htop")));
+
+        assert_eq!(names.get(InputMode::RenameTab), Some("logs"));
+        assert_eq!(names.get(InputMode::RenamePane), Some("htop"));
+        assert_eq!(names.get(InputMode::Normal), None);
+        assert_eq!(names.get(InputMode::Tab), None);
+    }
+
+    #[test]
+    fn keeps_names_cleared_by_renaming() {
+        let mut names = OriginalNames::default();
+        names.record(Some(&tab(0, "logs")), Some(&pane(1, "htop")));
+        names.record(Some(&tab(0, "")), Some(&pane(1, "")));
+
+        assert_eq!(names.get(InputMode::RenameTab), Some("logs"));
+        assert_eq!(names.get(InputMode::RenamePane), Some("htop"));
+    }
+
+    #[test]
+    fn drops_names_recorded_for_another_tab_or_pane() {
+        let mut names = OriginalNames::default();
+        names.record(Some(&tab(0, "logs")), Some(&pane(1, "htop")));
+        names.record(Some(&tab(1, "")), Some(&pane(2, "")));
+
+        assert_eq!(names.get(InputMode::RenameTab), None);
+        assert_eq!(names.get(InputMode::RenamePane), None);
+
+        // Plugin panes are numbered apart from terminal panes
+        names.record(None, Some(&pane(1, "htop")));
+        names.record(None, Some(&PaneInfo { is_plugin: true, ..pane(1, "") }));
+        assert_eq!(names.get(InputMode::RenamePane), None);
+    }
+
+    #[test]
+    fn lends_names_to_the_status_line() {
+        let mut names = OriginalNames::default();
+        names.record(Some(&tab(0, "logs")), None);
+
+        // Rendering borrows the recorded name rather than copying it
+        let (recorded, original) = (names.tab.as_ref().map(|(_, name)| name.as_ptr()), names.get(InputMode::RenameTab));
+        assert_eq!(original.map(str::as_ptr), recorded);
+
+        let mode_info = ModeInfo { mode: InputMode::RenameTab, ..ModeInfo::default() };
+        let tabs = [tab(0, "build")];
+        let status = StatusLine::builder(&mode_info).width(100).tabs(&tabs).original_name(original).build();
+        assert!(status.to_plain().ends_with("  logs → build"), "{:?}", status.to_plain());
+    }
+}